@@ -32,6 +32,52 @@ impl BinaryDense {
         BinaryDense { orders: Vec::new(), elements }
     }
 
+    /// Create an empty collection which can hold `orders` orders without
+    /// reallocating.
+    pub fn with_capacity(elements: usize, orders: usize) -> BinaryDense {
+        BinaryDense { orders: Vec::with_capacity(orders * elements), elements }
+    }
+
+    /// Shrink the capacity of the collection as much as possible.
+    pub fn shrink_to_fit(&mut self) {
+        self.orders.shrink_to_fit();
+    }
+
+    /// Remove every order, keeping the number of elements and the allocated
+    /// capacity.
+    pub fn clear(&mut self) {
+        self.orders.clear();
+    }
+
+    /// Keep only the first `len` orders, similar to [`Vec::truncate`]. Has no
+    /// effect if `len` is greater than or equal to the number of orders.
+    pub fn truncate(&mut self, len: usize) {
+        self.orders.truncate(len * self.elements);
+    }
+
+    /// Estimate of the number of bytes allocated on the heap by the
+    /// collection, based on the capacity of its buffers. Does not include the
+    /// size of the collection itself.
+    pub fn memory_usage(&self) -> usize {
+        self.orders.capacity() * size_of::<bool>()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = BinaryRef<'_>> {
+        (0..self.len()).map(|i| self.get(i))
+    }
+
+    /// Create a collection from `orders`, where the values of order `i` are
+    /// `orders[i * elements..(i + 1) * elements]`.
+    ///
+    /// Returns `Err` if the length of `orders` is not a multiple of
+    /// `elements`.
+    pub fn try_new(orders: Vec<bool>, elements: usize) -> Result<BinaryDense, &'static str> {
+        if !orders.len().is_multiple_of(elements) {
+            return Err("Length not a multiple of the number of elements");
+        }
+        Ok(BinaryDense { orders, elements })
+    }
+
     pub fn new_from_parts(orders: Vec<bool>, elements: usize) -> BinaryDense {
         assert!(orders.is_empty() && elements == 0 || orders.len().is_multiple_of(elements));
         BinaryDense { orders, elements }
@@ -142,7 +188,7 @@ mod tests {
     use quickcheck::{Arbitrary, Gen};
 
     use super::*;
-    use crate::tests::{BoundedArbitrary, std_rng};
+    use crate::tests::{BoundedArbitrary, shrink_dense, std_rng};
 
     fn valid(bd: &BinaryDense) -> bool {
         bd.elements == 0 && bd.orders.is_empty() || bd.orders.len() % bd.elements == 0
@@ -155,6 +201,12 @@ mod tests {
             orders.generate_uniform(&mut std_rng(g), orders_count);
             orders
         }
+
+        fn shrink(&self) -> Box<dyn Iterator<Item = Self>> {
+            shrink_dense(self, self.len(), self.elements, Self::truncate, |o, e| {
+                o.remove_element(e)
+            })
+        }
     }
 
     #[quickcheck]
@@ -162,10 +214,24 @@ mod tests {
         valid(&orders)
     }
 
+    #[quickcheck]
+    fn shrink_valid(orders: BinaryDense) -> bool {
+        orders.shrink().all(|o| valid(&o))
+    }
+
     #[quickcheck]
     fn to_cardinal(orders: BinaryDense) -> bool {
         let cardinal: CardinalDense = (&orders).try_into().unwrap();
         let around: BinaryDense = cardinal.to_binary_cutoff(1).unwrap();
         around == orders
     }
+
+    #[test]
+    fn try_new_ragged() {
+        assert!(BinaryDense::try_new(vec![true, false, true], 2).is_err());
+        assert!(BinaryDense::try_new(vec![true], 0).is_err());
+        let orders = BinaryDense::try_new(vec![true, false, false, true], 2).unwrap();
+        assert_eq!(orders.len(), 2);
+        assert_eq!(BinaryDense::try_new(Vec::new(), 0).unwrap().len(), 0);
+    }
 }
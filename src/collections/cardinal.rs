@@ -1,10 +1,15 @@
 use std::{cmp::Ordering, iter::repeat_n, ops::RangeBounds};
 
-use rand::distr::{Distribution, Uniform};
+use rand::{
+    Rng,
+    distr::{Distribution, Uniform},
+    seq::IndexedRandom,
+};
 
 use crate::{
     cardinal::{Cardinal, CardinalRef},
-    collections::{AddError, DenseOrders, binary::BinaryDense},
+    chain::Chain,
+    collections::{AddError, DenseOrders, SpecificDense, binary::BinaryDense},
     pairwise_lt,
 };
 
@@ -51,6 +56,72 @@ impl CardinalDense {
         CardinalDense { orders: Vec::new(), elements, min, max }
     }
 
+    /// Create an empty collection which can hold `orders` orders without
+    /// reallocating. See [`CardinalDense::new`].
+    pub fn with_capacity<R: RangeBounds<usize>>(
+        elements: usize,
+        range: R,
+        orders: usize,
+    ) -> CardinalDense {
+        let mut out = CardinalDense::new(elements, range);
+        out.orders.reserve_exact(orders * elements);
+        out
+    }
+
+    /// Create a collection from `orders`, where the values of order `i` are
+    /// `orders[i * elements..(i + 1) * elements]`.
+    ///
+    /// Returns `Err` if the length of `orders` is not a multiple of
+    /// `elements`, if `min > max` or if any value is not in `min..=max`.
+    pub fn try_new(
+        orders: Vec<usize>,
+        elements: usize,
+        min: usize,
+        max: usize,
+    ) -> Result<CardinalDense, &'static str> {
+        if min > max {
+            return Err("Minimum larger than maximum");
+        }
+        if !orders.len().is_multiple_of(elements) {
+            return Err("Length not a multiple of the number of elements");
+        }
+        if orders.iter().any(|v| !(min..=max).contains(v)) {
+            return Err("Element not in cardinal range");
+        }
+        Ok(CardinalDense { orders, elements, min, max })
+    }
+
+    /// Shrink the capacity of the collection as much as possible.
+    pub fn shrink_to_fit(&mut self) {
+        self.orders.shrink_to_fit();
+    }
+
+    /// Remove every order, keeping the number of elements and the allocated
+    /// capacity.
+    pub fn clear(&mut self) {
+        self.orders.clear();
+    }
+
+    /// Keep only the first `len` orders, similar to [`Vec::truncate`]. Has no
+    /// effect if `len` is greater than or equal to the number of orders.
+    pub fn truncate(&mut self, len: usize) {
+        self.orders.truncate(len * self.elements);
+    }
+
+    /// The packed orders in row-major layout, with length `len * elements`.
+    /// Order `i` is `as_slice()[i * elements..(i + 1) * elements]`, where value
+    /// `j` of the order is the value of element `j`.
+    pub fn as_slice(&self) -> &[usize] {
+        &self.orders
+    }
+
+    /// Estimate of the number of bytes allocated on the heap by the
+    /// collection, based on the capacity of its buffers. Does not include the
+    /// size of the collection itself.
+    pub fn memory_usage(&self) -> usize {
+        self.orders.capacity() * size_of::<usize>()
+    }
+
     pub fn min(&self) -> usize {
         self.min
     }
@@ -153,10 +224,77 @@ impl CardinalDense {
         Ok(BinaryDense::new_from_parts(binary_orders, self.elements))
     }
 
+    /// For every cutoff from `self.min()` up to the largest value given by any
+    /// order, the number of orders approving each element in
+    /// [`CardinalDense::to_binary_cutoff`] with that cutoff, i.e. the number
+    /// of orders giving the element a value of at least the cutoff. Higher
+    /// cutoffs approve no elements, so they are left out, and the result is
+    /// empty if there are no orders.
+    ///
+    /// ```
+    /// use orders::{
+    ///     cardinal::CardinalRef,
+    ///     collections::{CardinalDense, DenseOrders},
+    /// };
+    ///
+    /// let mut orders = CardinalDense::new(2, 1..=5);
+    /// assert!(orders.threshold_sweep().is_empty());
+    ///
+    /// orders.push(CardinalRef::new(&[1, 3])).unwrap();
+    /// orders.push(CardinalRef::new(&[2, 2])).unwrap();
+    /// // No order gives a value above 3, so the sweep stops there instead of
+    /// // at `orders.max()`, which may be unbounded.
+    /// assert_eq!(
+    ///     orders.threshold_sweep(),
+    ///     [(1, vec![2, 2]), (2, vec![1, 2]), (3, vec![0, 1])]
+    /// );
+    /// ```
+    pub fn threshold_sweep(&self) -> Vec<(usize, Vec<usize>)> {
+        let Some(&largest) = self.orders.iter().max() else {
+            return Vec::new();
+        };
+        let mut sweep: Vec<(usize, Vec<usize>)> =
+            (self.min..=largest).map(|cutoff| (cutoff, vec![0; self.elements])).collect();
+        for order in self.iter() {
+            for (c, &v) in order.values().iter().enumerate() {
+                sweep[v - self.min].1[c] += 1;
+            }
+        }
+        // Every order counting towards a cutoff also counts towards the lower
+        // cutoffs.
+        for i in (1..sweep.len()).rev() {
+            let (lower, higher) = sweep.split_at_mut(i);
+            for (l, h) in lower[i - 1].1.iter_mut().zip(&higher[0].1) {
+                *l += h;
+            }
+        }
+        sweep
+    }
+
+    /// Pick a winning element from each order, uniformly at random among the
+    /// elements with the highest value. See [`CardinalRef::winners`].
+    pub fn to_specific_using<R: Rng>(self, rng: &mut R) -> SpecificDense {
+        let mut out = SpecificDense::with_capacity(self.elements, self.len());
+        for order in self.iter() {
+            out.orders.push(*order.winners().choose(rng).unwrap());
+        }
+        out
+    }
+
     pub fn iter(&self) -> impl Iterator<Item = CardinalRef<'_>> {
         (0..self.len()).map(|i| self.get(i))
     }
 
+    /// Iterate over the scores of every order, allowing them to be modified in
+    /// place.
+    ///
+    /// Every score has to stay within `self.min()..=self.max()`, otherwise
+    /// other methods may panic or return incorrect results.
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut [usize]> {
+        // `elements` is zero only when there are no orders
+        self.orders.chunks_exact_mut(self.elements.max(1))
+    }
+
     /// Fill the given preference matrix for the elements listed in `keep`.
     ///
     /// The middle row in the matrix will always be zero
@@ -211,6 +349,51 @@ impl CardinalDense {
         a_v.cmp(&b_v)
     }
 
+    /// Rank the elements using [majority
+    /// judgment](https://en.wikipedia.org/wiki/Majority_judgment), where the
+    /// values are grades. Elements are ranked by their median grade, using the
+    /// lower median for an even number of orders. Two elements with the same
+    /// median grade are compared by removing one instance of that grade from
+    /// both and comparing their new medians, repeating until they differ.
+    /// Elements with identical grades are ranked by lowest index.
+    ///
+    /// ```
+    /// use orders::{
+    ///     cardinal::CardinalRef,
+    ///     collections::{CardinalDense, DenseOrders},
+    /// };
+    ///
+    /// let mut orders = CardinalDense::new(3, 0..=5);
+    /// for grades in [[5, 3, 4], [4, 3, 4], [3, 3, 2], [2, 3, 1], [1, 3, 0]] {
+    ///     orders.push(CardinalRef::new(&grades)).unwrap();
+    /// }
+    /// // Element 0 and 1 have median 3, but 1 wins after removing it
+    /// assert_eq!(orders.majority_judgment().into_inner(), [1, 0, 2]);
+    /// ```
+    pub fn majority_judgment(&self) -> Chain {
+        let mut grades = vec![Vec::with_capacity(self.len()); self.elements];
+        for order in self.iter() {
+            for (c, &v) in order.values().iter().enumerate() {
+                grades[c].push(v);
+            }
+        }
+        // The sequence of medians found by repeatedly removing the median
+        let medians: Vec<Vec<usize>> = grades
+            .into_iter()
+            .map(|mut g| {
+                g.sort_unstable();
+                let mut out = Vec::with_capacity(g.len());
+                while !g.is_empty() {
+                    out.push(g.remove((g.len() - 1) / 2));
+                }
+                out
+            })
+            .collect();
+        let mut order: Vec<usize> = (0..self.elements).collect();
+        order.sort_by(|&a, &b| medians[b].cmp(&medians[a]));
+        Chain::new(order)
+    }
+
     pub fn sum(&self) -> Result<Cardinal, SumError> {
         let mut out: Vec<usize> = Vec::new();
         if out.try_reserve(self.elements).is_err() {
@@ -326,9 +509,10 @@ impl<'a> DenseOrders<'a> for CardinalDense {
 #[cfg(test)]
 mod tests {
     use quickcheck::{Arbitrary, Gen};
+    use rand::{SeedableRng, rngs::StdRng};
 
     use super::*;
-    use crate::tests::{BoundedArbitrary, std_rng};
+    use crate::tests::{BoundedArbitrary, shrink_dense, std_rng};
 
     fn valid(cd: &CardinalDense) -> bool {
         if cd.elements == 0 {
@@ -361,6 +545,12 @@ mod tests {
             orders.generate_uniform(&mut std_rng(g), orders_count);
             orders
         }
+
+        fn shrink(&self) -> Box<dyn Iterator<Item = Self>> {
+            shrink_dense(self, self.len(), self.elements, Self::truncate, |o, e| {
+                o.remove_element(e)
+            })
+        }
     }
 
     #[quickcheck]
@@ -368,6 +558,87 @@ mod tests {
         valid(&orders)
     }
 
+    #[quickcheck]
+    fn shrink_valid(orders: CardinalDense) -> bool {
+        orders.shrink().all(|o| valid(&o))
+    }
+
+    #[quickcheck]
+    fn try_new_round_trip(orders: CardinalDense) -> bool {
+        let new =
+            CardinalDense::try_new(orders.orders.clone(), orders.elements, orders.min, orders.max);
+        new.is_ok_and(|new| new == orders)
+    }
+
+    #[test]
+    fn try_new_invalid() {
+        assert!(CardinalDense::try_new(vec![0, 1, 2, 1], 2, 0, 2).is_ok());
+        assert!(CardinalDense::try_new(vec![0, 1, 3, 1], 2, 0, 2).is_err());
+        assert!(CardinalDense::try_new(vec![0, 1, 2], 2, 0, 2).is_err());
+        assert!(CardinalDense::try_new(vec![], 2, 3, 2).is_err());
+        assert!(CardinalDense::try_new(vec![1], 0, 0, 2).is_err());
+    }
+
+    #[quickcheck]
+    fn as_slice_rows(orders: CardinalDense) -> bool {
+        let slice = orders.as_slice();
+        let n = orders.elements();
+        slice.len() == orders.len() * n
+            && orders.iter().enumerate().all(|(i, o)| o.values() == &slice[(i * n)..((i + 1) * n)])
+    }
+
+    #[quickcheck]
+    fn to_specific_using_max(orders: CardinalDense, seed: u64) -> bool {
+        let specific = orders.clone().to_specific_using(&mut StdRng::seed_from_u64(seed));
+        specific.len() == orders.len()
+            && specific.elements() == orders.elements()
+            && orders
+                .iter()
+                .zip(&specific.orders)
+                .all(|(order, &w)| order.values()[w] == *order.values().iter().max().unwrap())
+    }
+
+    #[quickcheck]
+    fn majority_judgment_median(orders: CardinalDense) -> bool {
+        if orders.is_empty() {
+            return true;
+        }
+        let ranking = orders.majority_judgment();
+        let median = |c: usize| {
+            let mut grades: Vec<usize> = orders.iter().map(|o| o.values()[c]).collect();
+            grades.sort_unstable();
+            grades[(grades.len() - 1) / 2]
+        };
+        let medians: Vec<usize> = ranking.order.iter().map(|&c| median(c)).collect();
+        medians.is_sorted_by(|a, b| a >= b)
+    }
+
+    #[test]
+    fn majority_judgment_tie_break() {
+        let mut orders = CardinalDense::new(3, 0..=5);
+        for grades in [[2, 3, 4], [2, 2, 4], [3, 2, 0], [3, 2, 0]] {
+            orders.push(CardinalRef::new(&grades)).unwrap();
+        }
+        // Medians are 2, 2 and 0. After removing a 2, element 0 has median 3
+        // and element 1 has 2.
+        assert_eq!(orders.majority_judgment(), Chain::new(vec![0, 1, 2]));
+    }
+
+    #[quickcheck]
+    fn iter_mut_clamp(mut orders: CardinalDense) -> bool {
+        let before = orders.clone();
+        let max = orders.max();
+        for order in orders.iter_mut() {
+            for v in order {
+                *v = (*v + 1).min(max);
+            }
+        }
+        valid(&orders)
+            && orders.iter().zip(before.iter()).all(|(a, b)| {
+                a.values().iter().zip(b.values()).all(|(&x, &y)| x == (y + 1).min(max))
+            })
+    }
+
     #[quickcheck]
     fn kp_transform_orders(cv: CardinalDense) -> bool {
         match cv.kp_transform() {
@@ -375,4 +646,29 @@ mod tests {
             Err(_) => true,
         }
     }
+
+    #[quickcheck]
+    fn threshold_sweep(orders: CardinalDense) -> bool {
+        let sweep = orders.threshold_sweep();
+        let first = sweep.first().is_none_or(|(cutoff, tally)| {
+            *cutoff == orders.min() && tally.iter().all(|&t| t == orders.len())
+        });
+        let non_increasing =
+            sweep.windows(2).all(|w| w[0].1.iter().zip(&w[1].1).all(|(a, b)| a >= b));
+        let matches = sweep.iter().all(|(cutoff, tally)| {
+            let binary = orders.to_binary_cutoff(*cutoff).unwrap();
+            (0..orders.elements())
+                .all(|c| binary.iter().filter(|o| o.values()[c]).count() == tally[c])
+        });
+        let last = sweep.last().map(|(cutoff, _)| cutoff) == orders.orders.iter().max();
+        first && non_increasing && matches && last
+    }
+
+    #[test]
+    fn threshold_sweep_unbounded() {
+        let mut orders = CardinalDense::new(2, 0..);
+        assert!(orders.threshold_sweep().is_empty());
+        orders.push(CardinalRef::new(&[0, 2])).unwrap();
+        assert_eq!(orders.threshold_sweep(), [(0, vec![1, 1]), (1, vec![0, 1]), (2, vec![0, 1])]);
+    }
 }
@@ -1,11 +1,17 @@
 // TODO: A lot of implementation details are shared between PartialRanking and
 // TotalRanking. Should they be combined somehow?
-use rand::seq::SliceRandom;
+use std::{cmp::Ordering, collections::HashMap};
 
+use rand::seq::{SliceRandom, index};
+
+#[cfg(feature = "rayon")]
+use crate::collections::PAR_CHUNK;
 use crate::{
-    chain::ChainRef,
+    Order, OrderRef,
+    chain::{Chain, ChainRef},
     collections::{AddError, DenseOrders},
-    get_order, pairwise_lt,
+    distribution::{Mallows, OrderDistribution, PlackettLuce},
+    unique_and_bounded,
 };
 
 /// Packed list of [`Chain`](crate::chain::Chain)
@@ -31,9 +37,1036 @@ impl ChainDense {
         ChainDense { orders: Vec::new(), elements }
     }
 
+    /// Create an empty collection which can hold `orders` orders without
+    /// reallocating.
+    pub fn with_capacity(elements: usize, orders: usize) -> Self {
+        ChainDense { orders: Vec::with_capacity(orders * elements), elements }
+    }
+
+    /// Shrink the capacity of the collection as much as possible.
+    pub fn shrink_to_fit(&mut self) {
+        self.orders.shrink_to_fit();
+    }
+
+    /// Remove every order, keeping the number of elements and the allocated
+    /// capacity.
+    pub fn clear(&mut self) {
+        self.orders.clear();
+    }
+
+    /// Keep only the first `len` orders, similar to [`Vec::truncate`]. Has no
+    /// effect if `len` is greater than or equal to the number of orders.
+    pub fn truncate(&mut self, len: usize) {
+        self.orders.truncate(len * self.elements);
+    }
+
+    /// The packed orders in row-major layout, with length `len * elements`.
+    /// Order `i` is `as_slice()[i * elements..(i + 1) * elements]`, listing its
+    /// elements from highest to lowest.
+    pub fn as_slice(&self) -> &[usize] {
+        &self.orders
+    }
+
+    /// Estimate of the number of bytes allocated on the heap by the
+    /// collection, based on the capacity of its buffers. Does not include the
+    /// size of the collection itself.
+    pub fn memory_usage(&self) -> usize {
+        self.orders.capacity() * size_of::<usize>()
+    }
+
     pub fn iter(&self) -> impl Iterator<Item = ChainRef<'_>> {
         (0..self.len()).map(|i| self.get(i))
     }
+
+    /// Move all orders from `other` to the end of `self`, leaving `other`
+    /// empty.
+    ///
+    /// Returns `Err` if the collections have a different number of elements.
+    pub fn append(&mut self, other: &mut ChainDense) -> Result<(), AddError> {
+        if self.elements != other.elements {
+            return Err(AddError::Elements);
+        }
+        self.orders.try_reserve(other.orders.len()).or(Err(AddError::Alloc))?;
+        self.orders.append(&mut other.orders);
+        Ok(())
+    }
+
+    /// Split the collection in two at index `at`, similar to
+    /// [`Vec::split_off`]. Returns the orders `[at, len)`, and `self` keeps
+    /// the orders `[0, at)`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `at > len`.
+    #[must_use]
+    pub fn split_off(&mut self, at: usize) -> ChainDense {
+        assert!(at <= self.len());
+        let orders = self.orders.split_off(at * self.elements);
+        ChainDense { orders, elements: self.elements }
+    }
+
+    /// Add a new element, ranked last in every order, and return it. This is
+    /// the inverse of [`DenseOrders::remove_element`] with the returned
+    /// element.
+    pub fn add_element(&mut self) -> usize {
+        let new = self.elements;
+        let len = self.len();
+        self.elements += 1;
+        self.orders.resize(len * self.elements, new);
+        for i in (0..len).rev() {
+            self.orders.copy_within((i * new)..((i + 1) * new), i * self.elements);
+            self.orders[i * self.elements + new] = new;
+        }
+        new
+    }
+
+    /// Remove and return the order at index `i`, replacing it with the last
+    /// order. This changes the order of the collection, but takes `O(elements)`
+    /// time. Returns `None` if `i` is out of bounds.
+    pub fn swap_remove(&mut self, i: usize) -> Option<Chain> {
+        if i >= self.len() {
+            return None;
+        }
+        let removed = self.get(i).to_owned();
+        let last = self.len() - 1;
+        self.orders.copy_within((last * self.elements).., i * self.elements);
+        self.orders.truncate(last * self.elements);
+        Some(removed)
+    }
+
+    /// Returns the index of the first order equal to `order`, or `None` if
+    /// there is no such order or if `order` has a different number of
+    /// elements.
+    pub fn position(&self, order: ChainRef) -> Option<usize> {
+        if order.elements() != self.elements {
+            return None;
+        }
+        self.iter().position(|o| o.order == order.order)
+    }
+
+    /// Returns `true` if the collection contains an order equal to `order`.
+    pub fn contains(&self, order: ChainRef) -> bool {
+        self.position(order).is_some()
+    }
+
+    /// Returns the index of the first occurrence of every distinct order,
+    /// together with how many times it occurs, in order of first occurrence.
+    fn distinct_counts(&self) -> Vec<(usize, usize)> {
+        let mut index: HashMap<&[usize], usize> = HashMap::new();
+        let mut counts: Vec<(usize, usize)> = Vec::new();
+        for (i, order) in self.iter().enumerate() {
+            let j = *index.entry(order.order).or_insert(counts.len());
+            if j == counts.len() {
+                counts.push((i, 0));
+            }
+            counts[j].1 += 1;
+        }
+        counts
+    }
+
+    /// Shannon entropy, in bits (base 2 logarithm), of the distribution of
+    /// distinct orders in the collection. Identical orders give an entropy of
+    /// 0, while `k` distinct orders occurring equally often give `log2(k)`.
+    /// An empty collection has an entropy of 0.
+    pub fn entropy(&self) -> f64 {
+        let len = self.len() as f64;
+        self.distinct_counts()
+            .into_iter()
+            .map(|(_, count)| {
+                let p = count as f64 / len;
+                -p * p.log2()
+            })
+            .sum()
+    }
+
+    /// Returns the index of the most common order and how many times it
+    /// occurs. Ties are broken by the earliest first occurrence.
+    fn most_common(&self) -> Option<(usize, usize)> {
+        self.distinct_counts().into_iter().rev().max_by_key(|&(_, count)| count)
+    }
+
+    /// Returns the most common order in the collection, or `None` if it is
+    /// empty. If several orders are equally common, the one occurring first
+    /// is returned.
+    pub fn mode(&self) -> Option<Chain> {
+        self.most_common().map(|(i, _)| self.get(i).to_owned())
+    }
+
+    /// Number of times [`ChainDense::mode`] occurs in the collection, or 0 if
+    /// it is empty.
+    pub fn mode_count(&self) -> usize {
+        self.most_common().map_or(0, |(_, count)| count)
+    }
+
+    /// Rename the elements of every order, where element `i` becomes element
+    /// `perm[i]`.
+    ///
+    /// Returns `Err` if `perm` is not a permutation of `0..self.elements()`.
+    ///
+    /// ```
+    /// use orders::{
+    ///     OrderRef,
+    ///     chain::ChainRef,
+    ///     collections::{ChainDense, DenseOrders},
+    /// };
+    ///
+    /// let mut orders = ChainDense::new(3);
+    /// orders.push(ChainRef::new(&[2, 0, 1])).unwrap();
+    /// orders.relabel(&[1, 2, 0]).unwrap();
+    /// assert_eq!(orders.get(0).to_owned().into_inner(), [0, 1, 2]);
+    /// ```
+    pub fn relabel(&mut self, perm: &[usize]) -> Result<(), &'static str> {
+        if perm.len() != self.elements || !unique_and_bounded(self.elements, perm) {
+            return Err("Not a permutation of the elements");
+        }
+        for v in &mut self.orders {
+            *v = perm[*v];
+        }
+        Ok(())
+    }
+
+    /// Returns a new collection, where every order only ranks the elements in
+    /// `keep`, in the same relative order. Element `keep[i]` becomes element
+    /// `i`.
+    ///
+    /// Returns `Err` if `keep` contains duplicates or elements not in the
+    /// collection.
+    ///
+    /// ```
+    /// use orders::{
+    ///     chain::ChainRef,
+    ///     collections::{ChainDense, DenseOrders},
+    /// };
+    ///
+    /// let mut orders = ChainDense::new(4);
+    /// orders.push(ChainRef::new(&[3, 0, 2, 1])).unwrap();
+    /// let projected = orders.project(&[1, 3]).unwrap();
+    /// assert_eq!(projected.as_slice(), [1, 0]);
+    /// ```
+    pub fn project(&self, keep: &[usize]) -> Result<ChainDense, &'static str> {
+        if !unique_and_bounded(self.elements, keep) {
+            return Err("Elements not unique or not in collection");
+        }
+        let mut new_index = vec![None; self.elements];
+        for (i, &c) in keep.iter().enumerate() {
+            new_index[c] = Some(i);
+        }
+        let mut out = ChainDense::with_capacity(keep.len(), self.len());
+        if !keep.is_empty() {
+            out.orders.extend(self.orders.iter().filter_map(|&c| new_index[c]));
+        }
+        Ok(out)
+    }
+
+    /// Swap `swaps` pairs of adjacent elements, each in a uniformly random
+    /// order at a uniformly random position. Does nothing if there are fewer
+    /// than 2 elements or no orders.
+    pub fn perturb_adjacent<R: rand::Rng>(&mut self, rng: &mut R, swaps: usize) {
+        if self.elements < 2 || self.is_empty() {
+            return;
+        }
+        for _ in 0..swaps {
+            let i = rng.random_range(0..self.len());
+            let p = rng.random_range(0..(self.elements - 1));
+            self.orders.swap(i * self.elements + p, i * self.elements + p + 1);
+        }
+    }
+
+    /// Returns a new collection of `k` orders chosen uniformly at random from
+    /// `self`, without replacement. If `k > self.len()`, then it's clamped to
+    /// `self.len()`, so every order is chosen in a random order.
+    pub fn sample<R: rand::Rng>(&self, rng: &mut R, k: usize) -> ChainDense {
+        let k = k.min(self.len());
+        let mut out = ChainDense::with_capacity(self.elements, k);
+        for i in index::sample(rng, self.len(), k) {
+            out.orders.extend_from_slice(self.get(i).order);
+        }
+        out
+    }
+
+    /// Returns a new collection of `k` orders chosen uniformly at random from
+    /// `self`, with replacement. Useful for bootstrapping.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `self` is empty and `k != 0`.
+    pub fn sample_with_replacement<R: rand::Rng>(&self, rng: &mut R, k: usize) -> ChainDense {
+        assert!(!self.is_empty() || k == 0);
+        let mut out = ChainDense::with_capacity(self.elements, k);
+        for _ in 0..k {
+            let i = rng.random_range(0..self.len());
+            out.orders.extend_from_slice(self.get(i).order);
+        }
+        out
+    }
+
+    /// Returns a mix of the orders of `a` and `b`, containing
+    /// `round(ratio * a.len())` orders sampled from `a` and
+    /// `round((1 - ratio) * b.len())` orders sampled from `b`, without
+    /// replacement, interleaved in a random order.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `a` and `b` have different numbers of elements, or if `ratio`
+    /// is not in `[0, 1]`.
+    pub fn blend<R: rand::Rng>(
+        rng: &mut R,
+        a: &ChainDense,
+        b: &ChainDense,
+        ratio: f64,
+    ) -> ChainDense {
+        assert!(a.elements == b.elements);
+        assert!((0.0..=1.0).contains(&ratio));
+        let from_a = a.sample(rng, (ratio * a.len() as f64).round() as usize);
+        let from_b = b.sample(rng, ((1.0 - ratio) * b.len() as f64).round() as usize);
+        let mut sources = vec![true; from_a.len()];
+        sources.resize(from_a.len() + from_b.len(), false);
+        sources.shuffle(rng);
+        let mut out = ChainDense::with_capacity(a.elements, sources.len());
+        let (mut iter_a, mut iter_b) = (from_a.iter(), from_b.iter());
+        for source in sources {
+            let order = if source { iter_a.next() } else { iter_b.next() };
+            out.orders.extend_from_slice(order.unwrap().order);
+        }
+        out
+    }
+
+    /// Returns a matrix `m`, where `m[a][b]` is the number of orders ranking
+    /// `a` higher than `b`.
+    pub fn pairwise_matrix(&self) -> Vec<Vec<usize>> {
+        let mut matrix = vec![vec![0; self.elements]; self.elements];
+        for order in self.iter() {
+            for (i, &a) in order.order.iter().enumerate() {
+                for &b in &order.order[(i + 1)..] {
+                    matrix[a][b] += 1;
+                }
+            }
+        }
+        matrix
+    }
+
+    /// Number of orders ranking `a` higher than `b`.
+    fn head_to_head(&self, a: usize, b: usize) -> usize {
+        self.iter()
+            .filter(|order| order.order.iter().find(|&&c| c == a || c == b) == Some(&a))
+            .count()
+    }
+
+    /// Whether `a` beats, ties or loses to `b` in a pairwise majority, i.e.
+    /// compares the number of orders ranking `a` higher than `b` with the
+    /// number of orders ranking `b` higher than `a`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `a` or `b` is not less than the number of elements.
+    ///
+    /// ```
+    /// use std::cmp::Ordering;
+    ///
+    /// use orders::{
+    ///     chain::ChainRef,
+    ///     collections::{ChainDense, DenseOrders},
+    /// };
+    ///
+    /// let mut orders = ChainDense::new(3);
+    /// orders.push(ChainRef::new(&[0, 1, 2])).unwrap();
+    /// orders.push(ChainRef::new(&[2, 1, 0])).unwrap();
+    /// orders.push(ChainRef::new(&[1, 0, 2])).unwrap();
+    /// assert_eq!(orders.beats(1, 2), Ordering::Greater);
+    /// assert_eq!(orders.beats(0, 1), Ordering::Less);
+    /// ```
+    pub fn beats(&self, a: usize, b: usize) -> Ordering {
+        assert!(a < self.elements && b < self.elements);
+        if a == b {
+            return Ordering::Equal;
+        }
+        let wins = self.head_to_head(a, b);
+        wins.cmp(&(self.len() - wins))
+    }
+
+    /// Returns `true` if more than `fraction` of the orders rank `a` higher
+    /// than `b`. A `fraction` of `0.5` is a simple majority and `2.0 / 3.0`
+    /// a two-thirds majority.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `a` or `b` is not less than the number of elements.
+    pub fn supermajority(&self, a: usize, b: usize, fraction: f64) -> bool {
+        assert!(a < self.elements && b < self.elements);
+        let wins = if a == b { 0 } else { self.head_to_head(a, b) };
+        wins as f64 > fraction * self.len() as f64
+    }
+
+    /// [Borda count](https://en.wikipedia.org/wiki/Borda_count) of every
+    /// element, where each order gives `elements - 1 - p` points to the
+    /// element at position `p`, starting from 0 at the top.
+    pub fn borda(&self) -> Vec<usize> {
+        let mut score = vec![0; self.elements];
+        for order in self.iter() {
+            for (p, &c) in order.order.iter().enumerate() {
+                score[c] += self.elements - 1 - p;
+            }
+        }
+        score
+    }
+
+    /// Score of every element by a [positional voting
+    /// rule](https://en.wikipedia.org/wiki/Positional_voting), where each
+    /// order gives `weights[p]` points to the element at position `p`,
+    /// starting from 0 at the top. For example, [`ChainDense::borda`] uses
+    /// the weights `[elements - 1, ..., 1, 0]` and plurality uses
+    /// `[1, 0, ..., 0]`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `weights` doesn't have one weight per element.
+    ///
+    /// ```
+    /// use orders::{
+    ///     chain::ChainRef,
+    ///     collections::{ChainDense, DenseOrders},
+    /// };
+    ///
+    /// let mut orders = ChainDense::new(3);
+    /// orders.push(ChainRef::new(&[0, 1, 2])).unwrap();
+    /// orders.push(ChainRef::new(&[1, 2, 0])).unwrap();
+    /// // Anti-plurality
+    /// assert_eq!(orders.positional_score(&[1, 1, 0]), [1, 2, 1]);
+    /// ```
+    pub fn positional_score(&self, weights: &[usize]) -> Vec<usize> {
+        assert!(weights.len() == self.elements);
+        let mut score = vec![0; self.elements];
+        for order in self.iter() {
+            for (&c, &w) in order.order.iter().zip(weights) {
+                score[c] += w;
+            }
+        }
+        score
+    }
+
+    /// The Borda points given by each order, in the same order as
+    /// [`ChainDense::iter`]. Summing them gives [`ChainDense::borda`].
+    ///
+    /// ```
+    /// use orders::{
+    ///     chain::ChainRef,
+    ///     collections::{ChainDense, DenseOrders},
+    /// };
+    ///
+    /// let mut orders = ChainDense::new(3);
+    /// orders.push(ChainRef::new(&[2, 0, 1])).unwrap();
+    /// orders.push(ChainRef::new(&[0, 1, 2])).unwrap();
+    /// let points: Vec<Vec<usize>> = orders.borda_contributions().collect();
+    /// assert_eq!(points, [[1, 0, 2], [2, 1, 0]]);
+    /// assert_eq!(orders.borda(), [3, 1, 2]);
+    /// ```
+    pub fn borda_contributions(&self) -> impl Iterator<Item = Vec<usize>> + '_ {
+        self.iter().map(|order| {
+            let mut points = vec![0; self.elements];
+            for (p, &c) in order.order.iter().enumerate() {
+                points[c] = self.elements - 1 - p;
+            }
+            points
+        })
+    }
+
+    /// Returns `out`, where `out[p]` is the number of orders ranking `element`
+    /// at position `p`, starting from 0 at the top. The result has length
+    /// `elements` and sums to `len`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `element >= self.elements()`.
+    pub fn rank_histogram(&self, element: usize) -> Vec<usize> {
+        assert!(element < self.elements, "Element not in collection");
+        let mut out = vec![0; self.elements];
+        for order in self.iter() {
+            let p = order.order.iter().position(|&c| c == element).unwrap();
+            out[p] += 1;
+        }
+        out
+    }
+
+    /// The median position of every element over all orders, where 0 is the
+    /// top position. With an even number of orders, the lower of the two
+    /// middle positions is used. Every median is 0 if the collection is
+    /// empty.
+    pub fn median_ranks(&self) -> Vec<usize> {
+        let mut positions = vec![Vec::with_capacity(self.len()); self.elements];
+        for order in self.iter() {
+            for (p, &c) in order.order.iter().enumerate() {
+                positions[c].push(p);
+            }
+        }
+        positions
+            .into_iter()
+            .map(|mut ps| {
+                if ps.is_empty() {
+                    return 0;
+                }
+                let mid = (ps.len() - 1) / 2;
+                *ps.select_nth_unstable(mid).1
+            })
+            .collect()
+    }
+
+    /// Rank the elements by their [median position](ChainDense::median_ranks),
+    /// with ties broken by lowest index.
+    ///
+    /// ```
+    /// use orders::{
+    ///     chain::ChainRef,
+    ///     collections::{ChainDense, DenseOrders},
+    /// };
+    ///
+    /// let mut orders = ChainDense::new(4);
+    /// orders.push(ChainRef::new(&[3, 0, 1, 2])).unwrap();
+    /// orders.push(ChainRef::new(&[0, 1, 2, 3])).unwrap();
+    /// orders.push(ChainRef::new(&[1, 0, 3, 2])).unwrap();
+    /// assert_eq!(orders.median_ranks(), [1, 1, 3, 2]);
+    /// // 0 and 1 have the same median position, so 0 is ranked higher
+    /// assert_eq!(orders.median_ranking().into_inner(), [0, 1, 3, 2]);
+    /// ```
+    pub fn median_ranking(&self) -> Chain {
+        let medians = self.median_ranks();
+        let mut order: Vec<usize> = (0..self.elements).collect();
+        order.sort_by_key(|&c| medians[c]);
+        Chain { order }
+    }
+
+    /// Ranking by [Baldwin's method](https://en.wikipedia.org/wiki/Nanson%27s_method#Baldwin_method).
+    /// The element with the lowest [Borda count](ChainDense::borda) is
+    /// repeatedly eliminated, recomputing the counts among the remaining
+    /// elements every round. Elements are ranked in the reverse order of
+    /// elimination, and ties are broken by eliminating the highest index first.
+    ///
+    /// A Condorcet winner always has more than the average Borda count among
+    /// the remaining elements, so it's never eliminated.
+    ///
+    /// ```
+    /// use orders::{
+    ///     chain::ChainRef,
+    ///     collections::{ChainDense, DenseOrders},
+    /// };
+    ///
+    /// let mut orders = ChainDense::new(4);
+    /// orders.push(ChainRef::new(&[2, 3, 0, 1])).unwrap();
+    /// orders.push(ChainRef::new(&[2, 0, 1, 3])).unwrap();
+    /// orders.push(ChainRef::new(&[3, 1, 0, 2])).unwrap();
+    /// orders.push(ChainRef::new(&[3, 0, 2, 1])).unwrap();
+    /// assert_eq!(orders.borda(), [6, 3, 7, 8]);
+    /// // Once 1 is eliminated, 2 has the highest count
+    /// assert_eq!(orders.baldwin().into_inner(), [2, 3, 0, 1]);
+    /// ```
+    pub fn baldwin(&self) -> Chain {
+        let mut remaining: Vec<usize> = (0..self.elements).collect();
+        let mut order = Vec::with_capacity(self.elements);
+        while !remaining.is_empty() {
+            let score = self.project(&remaining).unwrap().borda();
+            let (i, _) = score.iter().enumerate().rev().min_by_key(|&(_, s)| s).unwrap();
+            order.push(remaining.remove(i));
+        }
+        order.reverse();
+        Chain { order }
+    }
+
+    /// Ranking by [Nanson's method](https://en.wikipedia.org/wiki/Nanson%27s_method).
+    /// Every element with a [Borda count](ChainDense::borda) below the
+    /// average is eliminated each round, recomputing the counts among the
+    /// remaining elements. If every remaining element has the same count, they
+    /// are all eliminated.
+    ///
+    /// Elements eliminated in a later round are ranked higher, and elements
+    /// eliminated in the same round are ranked by their Borda count in that
+    /// round, with ties broken by lowest index.
+    ///
+    /// Like in [`ChainDense::baldwin`], a Condorcet winner is never below the
+    /// average, so it's ranked highest.
+    pub fn nanson(&self) -> Chain {
+        let mut remaining: Vec<usize> = (0..self.elements).collect();
+        let mut order = Vec::with_capacity(self.elements);
+        while !remaining.is_empty() {
+            let score = self.project(&remaining).unwrap().borda();
+            let total: usize = score.iter().sum();
+            let k = remaining.len();
+            let below: Vec<bool> = score.iter().map(|&s| s * k < total).collect();
+            let all = !below.contains(&true);
+            let mut eliminated = Vec::new();
+            let mut kept = Vec::new();
+            for (i, &c) in remaining.iter().enumerate() {
+                if all || below[i] {
+                    eliminated.push((score[i], c));
+                } else {
+                    kept.push(c);
+                }
+            }
+            // Reversed below, so the lowest ranked element comes first.
+            eliminated.sort_by(|a, b| a.0.cmp(&b.0).then(b.1.cmp(&a.1)));
+            order.extend(eliminated.into_iter().map(|(_, c)| c));
+            remaining = kept;
+        }
+        order.reverse();
+        Chain { order }
+    }
+
+    /// Find a [Kemeny ranking](https://en.wikipedia.org/wiki/Kemeny%E2%80%93Young_method),
+    /// an order which minimizes the sum of the [Kendall tau
+    /// distances](ChainRef::kendall_distance) to every order in the
+    /// collection. Returns the ranking together with that sum.
+    ///
+    /// The ranking is only guaranteed to be optimal if there are at most
+    /// [`KEMENY_EXACT_MAX`] elements, where it's found using branch and bound.
+    /// For more elements, a local search is used instead, which may return a
+    /// suboptimal ranking.
+    ///
+    /// ```
+    /// use orders::{
+    ///     chain::ChainRef,
+    ///     collections::{ChainDense, DenseOrders},
+    /// };
+    ///
+    /// let mut orders = ChainDense::new(3);
+    /// orders.push(ChainRef::new(&[0, 1, 2])).unwrap();
+    /// orders.push(ChainRef::new(&[1, 0, 2])).unwrap();
+    /// orders.push(ChainRef::new(&[0, 2, 1])).unwrap();
+    /// let (ranking, distance) = orders.kemeny();
+    /// assert_eq!(ranking.into_inner(), [0, 1, 2]);
+    /// assert_eq!(distance, 2);
+    /// ```
+    pub fn kemeny(&self) -> (Chain, usize) {
+        let matrix = self.pairwise_matrix();
+        let order = if self.elements <= KEMENY_EXACT_MAX {
+            kemeny_exact(&matrix)
+        } else {
+            kemeny_local(&matrix)
+        };
+        let distance = kemeny_distance(&matrix, &order);
+        (Chain { order }, distance)
+    }
+
+    /// Sum of the [Kendall tau distances](ChainRef::kendall_distance) from
+    /// `candidate` to every order, i.e. the objective minimized by
+    /// [`ChainDense::kemeny`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `candidate` has a different number of elements.
+    pub fn kendall_sum(&self, candidate: ChainRef) -> usize {
+        assert!(candidate.elements() == self.elements);
+        kemeny_distance(&self.pairwise_matrix(), candidate.order)
+    }
+
+    /// Returns the [Smith set](https://en.wikipedia.org/wiki/Smith_set), the
+    /// smallest non-empty set of elements which each beat every element
+    /// outside the set in a pairwise majority, sorted by index.
+    ///
+    /// This is the top strongly connected component of the graph with an edge
+    /// from `a` to `b` if `a` does not lose to `b`. If there is a Condorcet
+    /// winner, then it's the only element of the set. Otherwise, the set
+    /// contains a majority cycle. Returns an empty set if there are no
+    /// elements.
+    ///
+    /// ```
+    /// use orders::{
+    ///     chain::ChainRef,
+    ///     collections::{ChainDense, DenseOrders},
+    /// };
+    ///
+    /// let mut orders = ChainDense::new(4);
+    /// orders.push(ChainRef::new(&[0, 1, 2, 3])).unwrap();
+    /// orders.push(ChainRef::new(&[1, 2, 0, 3])).unwrap();
+    /// orders.push(ChainRef::new(&[2, 0, 1, 3])).unwrap();
+    /// assert_eq!(orders.top_cycle(), [0, 1, 2]);
+    /// ```
+    pub fn top_cycle(&self) -> Vec<usize> {
+        let n = self.elements;
+        let matrix = self.pairwise_matrix();
+        let mut reach: Vec<Vec<bool>> =
+            (0..n).map(|a| (0..n).map(|b| matrix[a][b] >= matrix[b][a]).collect()).collect();
+        for i in 0..n {
+            let through = reach[i].clone();
+            for row in &mut reach {
+                if row[i] {
+                    for (r, &t) in row.iter_mut().zip(&through) {
+                        *r |= t;
+                    }
+                }
+            }
+        }
+        // Every pair has an edge in at least one direction, so the components
+        // are totally ordered and the top one reaches every element.
+        (0..n).filter(|&a| reach[a].iter().all(|&r| r)).collect()
+    }
+
+    /// The element with the highest [Borda count](ChainDense::borda) among
+    /// the [Smith set](ChainDense::top_cycle), counting only the elements of
+    /// the Smith set. Ties are broken by lowest index.
+    ///
+    /// If there is a Condorcet winner, then it's the only element of the
+    /// Smith set, so it's chosen.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there are no elements.
+    ///
+    /// ```
+    /// use orders::{
+    ///     chain::ChainRef,
+    ///     collections::{ChainDense, DenseOrders},
+    /// };
+    ///
+    /// let mut orders = ChainDense::new(4);
+    /// for order in [[0, 2, 1, 3], [1, 3, 0, 2], [1, 3, 0, 2], [0, 2, 1, 3], [2, 1, 0, 3]] {
+    ///     orders.push(ChainRef::new(&order)).unwrap();
+    /// }
+    /// assert_eq!(orders.top_cycle(), [0, 1, 2]);
+    /// assert_eq!(orders.smith_borda(), 0);
+    /// // Counting 3, which is outside the Smith set, would favor 1
+    /// assert_eq!(orders.borda(), [9, 10, 7, 4]);
+    /// ```
+    pub fn smith_borda(&self) -> usize {
+        let smith = self.top_cycle();
+        assert!(!smith.is_empty());
+        let score = self.project(&smith).unwrap().borda();
+        let (i, _) = score.iter().enumerate().rev().max_by_key(|&(_, s)| s).unwrap();
+        smith[i]
+    }
+
+    /// Rank the elements using the [Schulze
+    /// method](https://en.wikipedia.org/wiki/Schulze_method). Elements are
+    /// ranked by how many other elements they beat using the strongest paths
+    /// in the pairwise matrix, with ties broken by lowest index.
+    ///
+    /// A Condorcet winner has a stronger path to every other element than
+    /// any path back to it, so it's ranked highest.
+    pub fn schulze(&self) -> Chain {
+        let n = self.elements;
+        let matrix = self.pairwise_matrix();
+        let mut strength = vec![vec![0; n]; n];
+        for a in 0..n {
+            for b in 0..n {
+                if matrix[a][b] > matrix[b][a] {
+                    strength[a][b] = matrix[a][b];
+                }
+            }
+        }
+        for i in 0..n {
+            for a in 0..n {
+                if a == i {
+                    continue;
+                }
+                for b in 0..n {
+                    if b == i || b == a {
+                        continue;
+                    }
+                    let through = strength[a][i].min(strength[i][b]);
+                    if through > strength[a][b] {
+                        strength[a][b] = through;
+                    }
+                }
+            }
+        }
+        let wins: Vec<usize> =
+            (0..n).map(|a| (0..n).filter(|&b| strength[a][b] > strength[b][a]).count()).collect();
+        let mut order: Vec<usize> = (0..n).collect();
+        order.sort_by(|&a, &b| wins[b].cmp(&wins[a]));
+        Chain { order }
+    }
+
+    /// Sample and add `new_orders` orders from the distribution `dist`, using
+    /// random numbers from `rng`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `dist` generates orders of the wrong number of elements.
+    pub fn generate<R: rand::Rng, D: OrderDistribution<Chain>>(
+        &mut self,
+        rng: &mut R,
+        dist: &D,
+        new_orders: usize,
+    ) {
+        if self.elements == 0 {
+            return;
+        }
+        self.orders.reserve(self.elements * new_orders);
+        for _ in 0..new_orders {
+            let order = dist.sample(rng, self.elements);
+            assert!(order.elements() == self.elements);
+            self.orders.extend_from_slice(&order.order);
+        }
+    }
+
+    /// Sample and add `new_orders` orders from a [`PlackettLuce`]
+    /// distribution with the given `weights`, using random numbers from `rng`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `weights.len() != self.elements()` or if any weight is not
+    /// positive and finite.
+    pub fn generate_plackett_luce<R: rand::Rng>(
+        &mut self,
+        rng: &mut R,
+        new_orders: usize,
+        weights: &[f64],
+    ) {
+        assert!(weights.len() == self.elements);
+        self.generate(rng, &PlackettLuce::new(weights.to_vec()), new_orders);
+    }
+
+    /// Sample and add `new_orders` orders from a [`Mallows`] distribution
+    /// centered on `center`, using random numbers from `rng`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `phi` is not in `(0, 1]` or if `center` has the wrong number
+    /// of elements.
+    pub fn generate_mallows<R: rand::Rng>(
+        &mut self,
+        rng: &mut R,
+        new_orders: usize,
+        center: ChainRef,
+        phi: f64,
+    ) {
+        assert!(center.elements() == self.elements);
+        self.generate(rng, &Mallows::new(center.to_owned(), phi), new_orders);
+    }
+
+    /// Parallel version of [`DenseOrders::generate_uniform`].
+    ///
+    /// The orders are generated in chunks, each using its own rng seeded from
+    /// `rng`, so the result is the same for a given `rng` regardless of the
+    /// number of threads. It will not be the same as the result of
+    /// `generate_uniform`, but the orders follow the same distribution.
+    #[cfg(feature = "rayon")]
+    pub fn generate_uniform_par<R: rand::Rng>(&mut self, rng: &mut R, new_orders: usize) {
+        use rand::{SeedableRng, rngs::StdRng};
+        use rayon::prelude::*;
+
+        if self.elements == 0 || new_orders == 0 {
+            return;
+        }
+        let elements = self.elements;
+        let rngs: Vec<StdRng> =
+            (0..new_orders.div_ceil(PAR_CHUNK)).map(|_| StdRng::from_rng(rng)).collect();
+        let start = self.orders.len();
+        self.orders.resize(start + elements * new_orders, 0);
+        self.orders[start..].par_chunks_mut(elements * PAR_CHUNK).zip(rngs).for_each(
+            |(chunk, mut rng)| {
+                for order in chunk.chunks_exact_mut(elements) {
+                    for (i, el) in order.iter_mut().enumerate() {
+                        *el = i;
+                    }
+                    order.shuffle(&mut rng);
+                }
+            },
+        );
+    }
+}
+
+/// Compare two collections order by order. Returns a matrix `m`, where
+/// `m[i][j]` is the fraction of orders in `a` which agree with the order at the
+/// same index in `b` on whether `i` is ranked higher than `j`. If the
+/// collections are empty, then every entry is `1.0`.
+///
+/// # Panics
+///
+/// Panics if `a` and `b` have different numbers of elements or orders.
+///
+/// ```
+/// use orders::{
+///     chain::ChainRef,
+///     collections::{ChainDense, DenseOrders, pairwise_agreement},
+/// };
+///
+/// let mut a = ChainDense::new(3);
+/// a.push(ChainRef::new(&[0, 1, 2])).unwrap();
+/// let mut b = ChainDense::new(3);
+/// b.push(ChainRef::new(&[1, 0, 2])).unwrap();
+/// let m = pairwise_agreement(&a, &b);
+/// assert_eq!(m[0][1], 0.0);
+/// assert_eq!(m[0][2], 1.0);
+/// ```
+pub fn pairwise_agreement(a: &ChainDense, b: &ChainDense) -> Vec<Vec<f64>> {
+    assert!(a.elements == b.elements && a.len() == b.len());
+    let n = a.elements;
+    let mut agree = vec![vec![0usize; n]; n];
+    let mut pos_a = vec![0; n];
+    let mut pos_b = vec![0; n];
+    for (x, y) in a.iter().zip(b.iter()) {
+        for (i, (&ea, &eb)) in x.order.iter().zip(y.order).enumerate() {
+            pos_a[ea] = i;
+            pos_b[eb] = i;
+        }
+        for i in 0..n {
+            for j in 0..n {
+                if (pos_a[i] < pos_a[j]) == (pos_b[i] < pos_b[j]) {
+                    agree[i][j] += 1;
+                }
+            }
+        }
+    }
+    let len = a.len();
+    agree
+        .into_iter()
+        .map(|row| {
+            row.into_iter().map(|c| if len == 0 { 1.0 } else { c as f64 / len as f64 }).collect()
+        })
+        .collect()
+}
+
+/// Estimates the strength of every element from a pairwise `matrix`, where
+/// `matrix[a][b]` is the number of times `a` beat `b`, using `iters`
+/// iterations of the MM algorithm for the
+/// [Bradley–Terry model](https://en.wikipedia.org/wiki/Bradley%E2%80%93Terry_model).
+/// The strengths sum to 1, unless `matrix` is empty.
+///
+/// An element which never wins gets strength 0, and an element which is never
+/// compared keeps its initial strength.
+///
+/// # Panics
+///
+/// Panics if `matrix` is not square.
+///
+/// ```
+/// use orders::collections::bradley_terry;
+///
+/// let strengths = bradley_terry(&[vec![0, 3], vec![1, 0]], 100);
+/// assert!((strengths[0] - 0.75).abs() < 1e-9);
+/// assert!((strengths[1] - 0.25).abs() < 1e-9);
+/// ```
+pub fn bradley_terry(matrix: &[Vec<usize>], iters: usize) -> Vec<f64> {
+    let n = matrix.len();
+    assert!(matrix.iter().all(|row| row.len() == n));
+    let wins: Vec<usize> = matrix.iter().map(|row| row.iter().sum()).collect();
+    let mut strength = vec![1.0 / n as f64; n];
+    let mut next = vec![0.0; n];
+    for _ in 0..iters {
+        for (i, next_i) in next.iter_mut().enumerate() {
+            let mut denominator = 0.0;
+            for j in 0..n {
+                let games = matrix[i][j] + matrix[j][i];
+                if i != j && games != 0 {
+                    denominator += games as f64 / (strength[i] + strength[j]);
+                }
+            }
+            *next_i = if denominator == 0.0 { strength[i] } else { wins[i] as f64 / denominator };
+        }
+        let total: f64 = next.iter().sum();
+        for (s, x) in strength.iter_mut().zip(&next) {
+            *s = x / total;
+        }
+    }
+    strength
+}
+
+/// The largest number of elements for which [`ChainDense::kemeny`] is
+/// guaranteed to find an optimal ranking.
+pub const KEMENY_EXACT_MAX: usize = 9;
+
+// The sum of Kendall tau distances from `order` to the orders which produced
+// the pairwise `matrix`.
+fn kemeny_distance(matrix: &[Vec<usize>], order: &[usize]) -> usize {
+    let mut distance = 0;
+    for (i, &a) in order.iter().enumerate() {
+        for &b in &order[(i + 1)..] {
+            distance += matrix[b][a];
+        }
+    }
+    distance
+}
+
+fn kemeny_exact(matrix: &[Vec<usize>]) -> Vec<usize> {
+    let n = matrix.len();
+    let mut best = (usize::MAX, Vec::new());
+    let mut prefix = Vec::with_capacity(n);
+    let mut placed = vec![false; n];
+    kemeny_search(matrix, &mut prefix, &mut placed, 0, &mut best);
+    best.1
+}
+
+// Depth first search over every ranking starting with `prefix`, where
+// `distance` is the distance contributed by the elements in `prefix`.
+fn kemeny_search(
+    matrix: &[Vec<usize>],
+    prefix: &mut Vec<usize>,
+    placed: &mut [bool],
+    distance: usize,
+    best: &mut (usize, Vec<usize>),
+) {
+    let n = matrix.len();
+    if prefix.len() == n {
+        if distance < best.0 {
+            *best = (distance, prefix.clone());
+        }
+        return;
+    }
+
+    // Every remaining pair will at least disagree with the minority of orders.
+    let mut bound = distance;
+    for a in (0..n).filter(|&a| !placed[a]) {
+        for b in ((a + 1)..n).filter(|&b| !placed[b]) {
+            bound += matrix[a][b].min(matrix[b][a]);
+        }
+    }
+    if bound >= best.0 {
+        return;
+    }
+
+    for a in 0..n {
+        if placed[a] {
+            continue;
+        }
+        let added: usize = (0..n).filter(|&b| !placed[b] && b != a).map(|b| matrix[b][a]).sum();
+        placed[a] = true;
+        prefix.push(a);
+        kemeny_search(matrix, prefix, placed, distance + added, best);
+        prefix.pop();
+        placed[a] = false;
+    }
+}
+
+// Start with the elements sorted by their number of pairwise wins, then
+// repeatedly move single elements to the position which decreases the distance
+// the most.
+fn kemeny_local(matrix: &[Vec<usize>]) -> Vec<usize> {
+    let n = matrix.len();
+    let wins: Vec<usize> =
+        (0..n).map(|a| (0..n).filter(|&b| matrix[a][b] > matrix[b][a]).count()).collect();
+    let mut order: Vec<usize> = (0..n).collect();
+    order.sort_by(|&a, &b| wins[b].cmp(&wins[a]));
+
+    let mut improved = true;
+    while improved {
+        improved = false;
+        for i in 0..n {
+            let el = order[i];
+            let mut best_delta = 0;
+            let mut best_j = i;
+            let mut delta: isize = 0;
+            for j in (0..i).rev() {
+                let other = order[j];
+                delta += matrix[other][el] as isize - matrix[el][other] as isize;
+                if delta < best_delta {
+                    best_delta = delta;
+                    best_j = j;
+                }
+            }
+            delta = 0;
+            for (j, &other) in order.iter().enumerate().skip(i + 1) {
+                delta += matrix[el][other] as isize - matrix[other][el] as isize;
+                if delta < best_delta {
+                    best_delta = delta;
+                    best_j = j;
+                }
+            }
+            if best_j != i {
+                order.remove(i);
+                order.insert(best_j, el);
+                improved = true;
+            }
+        }
+    }
+    order
 }
 
 impl<'a> DenseOrders<'a> for ChainDense {
@@ -71,33 +1104,20 @@ impl<'a> DenseOrders<'a> for ChainDense {
     }
 
     fn remove_element(&mut self, target: usize) -> Result<(), &'static str> {
-        let targets = &[target];
-        if targets.is_empty() {
-            return Ok(());
-        }
-        debug_assert!(pairwise_lt(targets));
-        let new_elements = self.elements - targets.len();
-        for i in 0..self.len() {
-            let mut t_i = 0;
-            let mut offset = 0;
-            for j in 0..self.elements {
-                if targets[t_i] == j {
-                    t_i += 1;
-                    offset += 1;
-                } else {
-                    let old_index = i * self.elements + j;
-                    let new_index = i * new_elements + (j - offset);
-                    debug_assert!(new_index <= old_index);
-                    self.orders[new_index] = self.orders[old_index];
-                }
+        if self.elements <= target {
+            return Err("Element not in collection");
+        }
+        let len = self.len();
+        let mut written = 0;
+        for i in 0..(len * self.elements) {
+            let el = self.orders[i];
+            if el != target {
+                self.orders[written] = if el > target { el - 1 } else { el };
+                written += 1;
             }
-            let new_order = &mut self.orders[(i * new_elements)..((i + 1) * new_elements)];
-
-            // TODO: Can we do this in place?
-            new_order.clone_from_slice(&get_order(new_order, false));
         }
-        self.orders.truncate(self.len() * new_elements);
-        self.elements = new_elements;
+        self.elements -= 1;
+        self.orders.truncate(len * self.elements);
         Ok(())
     }
 
@@ -117,9 +1137,13 @@ impl<'a> DenseOrders<'a> for ChainDense {
 #[cfg(test)]
 mod tests {
     use quickcheck::{Arbitrary, Gen};
+    use rand::{SeedableRng, rngs::StdRng};
 
     use super::*;
-    use crate::tests::{BoundedArbitrary, std_rng};
+    use crate::{
+        Order, OrderOwned,
+        tests::{BoundedArbitrary, shrink_dense, std_rng},
+    };
 
     // Check if a given total ranking is valid, i.e.
     // 1. len(orders) % elements == 0
@@ -160,10 +1184,720 @@ mod tests {
             orders.generate_uniform(&mut std_rng(g), orders_count);
             orders
         }
+
+        fn shrink(&self) -> Box<dyn Iterator<Item = Self>> {
+            shrink_dense(self, self.len(), self.elements, Self::truncate, |o, e| {
+                o.remove_element(e)
+            })
+        }
     }
 
     #[quickcheck]
     fn generate(orders: ChainDense) -> bool {
         valid(&orders)
     }
+
+    #[quickcheck]
+    fn shrink_valid(orders: ChainDense) -> bool {
+        orders.shrink().all(|o| valid(&o))
+    }
+
+    #[quickcheck]
+    fn swap_remove(mut orders: ChainDense, i: usize) -> bool {
+        if orders.is_empty() {
+            return orders.swap_remove(i).is_none();
+        }
+        let i = i % orders.len();
+        let before = orders.clone();
+        let removed = orders.swap_remove(i).unwrap();
+        let moved = i == orders.len() || orders.get(i).order == before.get(before.len() - 1).order;
+        valid(&orders)
+            && orders.len() + 1 == before.len()
+            && removed.as_ref().order == before.get(i).order
+            && moved
+    }
+
+    #[test]
+    fn position() {
+        let mut orders = ChainDense::new(4);
+        orders.generate_uniform(&mut StdRng::seed_from_u64(0), 5);
+        let known = ChainRef::new(&[3, 1, 0, 2]);
+        orders.push(known).unwrap();
+        let first = orders.iter().position(|o| o.order == known.order).unwrap();
+        assert_eq!(orders.position(known), Some(first));
+        assert!(orders.contains(known));
+        assert!(!orders.contains(ChainRef::new(&[0, 1, 2])));
+    }
+
+    #[quickcheck]
+    fn truncate_clear(orders: ChainDense, len: usize) -> bool {
+        let len = len % (orders.len() + 2);
+        let mut truncated = orders.clone();
+        truncated.truncate(len);
+        let prefix = truncated.len() == len.min(orders.len())
+            && truncated.iter().zip(orders.iter()).all(|(a, b)| a.order == b.order);
+        truncated.clear();
+        prefix && truncated.is_empty() && truncated.elements() == orders.elements()
+    }
+
+    #[quickcheck]
+    fn with_capacity_push(orders: ChainDense) -> bool {
+        let mut a = ChainDense::with_capacity(orders.elements, orders.len());
+        let capacity = a.orders.capacity();
+        let mut b = ChainDense::new(orders.elements);
+        for order in orders.iter() {
+            a.push(order).unwrap();
+            b.push(order).unwrap();
+        }
+        b.shrink_to_fit();
+        a == b && a.orders.capacity() == capacity
+    }
+
+    #[quickcheck]
+    fn generate_plackett_luce(orders: ChainDense, weights: Vec<u8>) -> bool {
+        let mut orders = orders;
+        let weights: Vec<f64> =
+            (0..orders.elements).map(|i| *weights.get(i).unwrap_or(&1) as f64 + 1.0).collect();
+        orders.generate_plackett_luce(&mut StdRng::seed_from_u64(0), 5, &weights);
+        valid(&orders)
+    }
+
+    #[test]
+    fn plackett_luce_weights() {
+        const ORDERS: usize = 10_000;
+        let mut rng = StdRng::seed_from_u64(1);
+        let mut orders = ChainDense::new(3);
+        orders.generate_plackett_luce(&mut rng, ORDERS, &[1.0, 1.0, 8.0]);
+        let mut firsts = [0usize; 3];
+        for order in orders.iter() {
+            firsts[order.order[0]] += 1;
+        }
+        // Element 2 should be the first element 80% of the time.
+        assert!(7_500 < firsts[2] && firsts[2] < 8_500);
+        assert!(firsts[0].abs_diff(firsts[1]) < 500);
+    }
+
+    #[quickcheck]
+    fn generate_mallows(orders: ChainDense, phi: u8) -> bool {
+        let mut orders = orders;
+        let center = Chain::random(&mut StdRng::seed_from_u64(0), orders.elements);
+        let phi = (phi as f64 + 1.0) / 256.0;
+        orders.generate_mallows(&mut StdRng::seed_from_u64(1), 5, center.as_ref(), phi);
+        valid(&orders)
+    }
+
+    #[test]
+    fn mallows_distance() {
+        const ORDERS: usize = 1000;
+        let mut rng = StdRng::seed_from_u64(2);
+        let center = Chain::random(&mut rng, 8);
+        let mut last_mean = f64::INFINITY;
+        for phi in [1.0, 0.8, 0.5, 0.2, 0.01] {
+            let mut orders = ChainDense::new(8);
+            orders.generate_mallows(&mut rng, ORDERS, center.as_ref(), phi);
+            let total: usize = orders.iter().map(|o| o.kendall_distance(center.as_ref())).sum();
+            let mean = total as f64 / ORDERS as f64;
+            assert!(mean < last_mean);
+            last_mean = mean;
+        }
+        // A uniformly random order has a mean distance of 8 * 7 / 4 = 14, while
+        // `phi = 0.01` should almost always give `center`.
+        assert!(last_mean < 0.1);
+    }
+
+    #[test]
+    #[should_panic]
+    fn mallows_phi_zero() {
+        let center = Chain::new_default(3);
+        ChainDense::new(3).generate_mallows(&mut StdRng::seed_from_u64(0), 1, center.as_ref(), 0.0);
+    }
+
+    #[quickcheck]
+    fn kemeny_distance(orders: ChainDense) -> bool {
+        let (ranking, distance) = orders.kemeny();
+        let sum = |r: ChainRef| orders.iter().map(|o| o.kendall_distance(r)).sum::<usize>();
+        sum(ranking.as_ref()) == distance && orders.iter().all(|o| distance <= sum(o))
+    }
+
+    #[quickcheck]
+    fn kemeny_local_identical(order: Chain, copies: u8) -> bool {
+        if order.elements() == 0 {
+            return true;
+        }
+        let mut orders = ChainDense::new(order.elements());
+        for _ in 0..=copies {
+            orders.push(order.as_ref()).unwrap();
+        }
+        let matrix = orders.pairwise_matrix();
+        kemeny_local(&matrix) == order.order && orders.kemeny().0.order == order.order
+    }
+
+    // The example from the Wikipedia article on the Schulze method, where the
+    // candidates A, B, C, D, E are 0, 1, 2, 3, 4.
+    #[test]
+    fn schulze_example() {
+        let ballots: [(usize, [usize; 5]); 8] = [
+            (5, [0, 2, 1, 4, 3]),
+            (5, [0, 3, 4, 2, 1]),
+            (8, [1, 4, 3, 0, 2]),
+            (3, [2, 0, 1, 4, 3]),
+            (7, [2, 0, 4, 1, 3]),
+            (2, [2, 1, 0, 3, 4]),
+            (7, [3, 2, 4, 1, 0]),
+            (8, [4, 1, 0, 3, 2]),
+        ];
+        let mut orders = ChainDense::new(5);
+        for (count, order) in ballots {
+            for _ in 0..count {
+                orders.push(ChainRef::new(&order)).unwrap();
+            }
+        }
+        assert_eq!(orders.schulze().into_inner(), [4, 0, 2, 1, 3]);
+    }
+
+    #[quickcheck]
+    fn schulze_condorcet(orders: ChainDense) -> bool {
+        let n = orders.elements;
+        let matrix = orders.pairwise_matrix();
+        let condorcet = (0..n).find(|&a| (0..n).all(|b| a == b || matrix[a][b] > matrix[b][a]));
+        match condorcet {
+            Some(winner) => orders.schulze().order[0] == winner,
+            None => true,
+        }
+    }
+
+    #[quickcheck]
+    fn top_cycle_dominant(orders: ChainDense) -> bool {
+        let matrix = orders.pairwise_matrix();
+        let smith = orders.top_cycle();
+        let outside: Vec<usize> = (0..orders.elements).filter(|c| !smith.contains(c)).collect();
+        let dominant = smith.iter().all(|&a| outside.iter().all(|&b| matrix[a][b] > matrix[b][a]));
+        // Compare with the smallest dominant set found by brute force
+        let n = orders.elements.min(8);
+        let smallest = (1..(1usize << n))
+            .filter(|set| {
+                (0..n).filter(|a| set & (1 << a) != 0).all(|a| {
+                    (0..n).filter(|b| set & (1 << b) == 0).all(|b| matrix[a][b] > matrix[b][a])
+                })
+            })
+            .map(|set| set.count_ones() as usize)
+            .min()
+            .unwrap_or(0);
+        dominant && (orders.elements > 8 || smith.len() == smallest)
+    }
+
+    #[test]
+    fn top_cycle_condorcet_winner() {
+        let mut orders = ChainDense::new(3);
+        for order in [[0, 1, 2], [1, 2, 0], [2, 0, 1]] {
+            orders.push(Chain::new(order.to_vec()).as_ref()).unwrap();
+        }
+        assert_eq!(orders.top_cycle(), [0, 1, 2]);
+        orders.push(ChainRef::new(&[1, 0, 2])).unwrap();
+        orders.push(ChainRef::new(&[1, 2, 0])).unwrap();
+        assert_eq!(orders.top_cycle(), [1]);
+    }
+
+    #[quickcheck]
+    fn borda_contributions_sum(orders: ChainDense) -> bool {
+        let mut sum = vec![0; orders.elements];
+        for points in orders.borda_contributions() {
+            for (s, p) in sum.iter_mut().zip(points) {
+                *s += p;
+            }
+        }
+        sum == orders.borda()
+    }
+
+    #[quickcheck]
+    fn median_ranks_identical(order: Chain, copies: u8) -> bool {
+        if order.elements() == 0 {
+            return true;
+        }
+        let mut orders = ChainDense::new(order.elements());
+        for _ in 0..(copies % 8 + 1) {
+            orders.push(order.as_ref()).unwrap();
+        }
+        orders.median_ranking() == order
+    }
+
+    #[quickcheck]
+    fn rank_histogram_sum(orders: ChainDense, element: usize) -> bool {
+        if orders.elements == 0 {
+            return true;
+        }
+        let element = element % orders.elements;
+        let histogram = orders.rank_histogram(element);
+        let borda: usize =
+            histogram.iter().enumerate().map(|(p, count)| (orders.elements - 1 - p) * count).sum();
+        histogram.len() == orders.elements
+            && histogram.iter().sum::<usize>() == orders.len()
+            && borda == orders.borda()[element]
+    }
+
+    #[test]
+    #[should_panic]
+    fn rank_histogram_out_of_range() {
+        ChainDense::new(3).rank_histogram(3);
+    }
+
+    #[quickcheck]
+    fn median_ranks_bounds(orders: ChainDense) -> bool {
+        orders.median_ranks().iter().all(|&m| m < orders.elements)
+    }
+
+    #[quickcheck]
+    fn append_push(mut a: ChainDense, new_orders: u8) -> bool {
+        if a.append(&mut ChainDense::new(a.elements + 1)).is_ok() {
+            return false;
+        }
+        let mut b = ChainDense::new(a.elements);
+        if a.elements != 0 {
+            b.generate_uniform(&mut StdRng::seed_from_u64(0), new_orders as usize);
+        }
+        let mut pushed = a.clone();
+        for order in b.iter() {
+            pushed.push(order).unwrap();
+        }
+        a.append(&mut b).unwrap();
+        a == pushed && b.is_empty()
+    }
+
+    #[quickcheck]
+    fn split_off_append(orders: ChainDense, at: usize) -> bool {
+        let mut a = orders.clone();
+        let mut b = a.split_off(at % (orders.len() + 1));
+        a.append(&mut b).unwrap();
+        a == orders
+    }
+
+    #[test]
+    fn split_off_ends() {
+        let mut orders = ChainDense::new(4);
+        orders.generate_uniform(&mut StdRng::seed_from_u64(0), 10);
+        let mut a = orders.clone();
+        assert!(a.split_off(10).is_empty());
+        assert_eq!(a, orders);
+        let b = a.split_off(0);
+        assert!(a.is_empty() && a.elements() == 4);
+        assert_eq!(b, orders);
+    }
+
+    #[quickcheck]
+    fn pairwise_agreement_reversed(orders: ChainDense) -> bool {
+        let mut reversed = ChainDense::new(orders.elements);
+        for order in orders.iter() {
+            let rev: Vec<usize> = order.order.iter().rev().copied().collect();
+            reversed.push(ChainRef::new(&rev)).unwrap();
+        }
+        let same = pairwise_agreement(&orders, &orders);
+        let opposite = pairwise_agreement(&orders, &reversed);
+        let n = orders.elements;
+        same.iter().flatten().all(|&x| x == 1.0)
+            && (0..n).all(|i| {
+                (0..n)
+                    .all(|j| opposite[i][j] == if i == j || orders.is_empty() { 1.0 } else { 0.0 })
+            })
+    }
+
+    #[test]
+    fn generate_custom() {
+        // Always returns the order `0 > 1 > ... > n - 1`.
+        struct Identity;
+        impl OrderDistribution<Chain> for Identity {
+            fn sample<R: rand::Rng>(&self, _: &mut R, elements: usize) -> Chain {
+                Chain::new((0..elements).collect())
+            }
+        }
+
+        let mut orders = ChainDense::new(4);
+        orders.generate(&mut StdRng::seed_from_u64(0), &Identity, 3);
+        assert_eq!(orders.len(), 3);
+        assert!(orders.iter().all(|o| o.order == [0, 1, 2, 3]));
+    }
+
+    #[quickcheck]
+    fn entropy_bounds(orders: ChainDense) -> bool {
+        let entropy = orders.entropy();
+        entropy >= 0.0 && entropy <= (orders.len().max(1) as f64).log2() + 1e-9
+    }
+
+    #[test]
+    fn entropy_extremes() {
+        let order = Chain::new(vec![2, 0, 1]);
+        let mut identical = ChainDense::new(3);
+        for _ in 0..5 {
+            identical.push(order.as_ref()).unwrap();
+        }
+        assert_eq!(identical.entropy(), 0.0);
+        assert_eq!(ChainDense::new(3).entropy(), 0.0);
+
+        let mut spread = ChainDense::new(3);
+        for _ in 0..2 {
+            for order in [[0, 1, 2], [1, 2, 0], [2, 0, 1], [0, 2, 1]] {
+                spread.push(Chain::new(order.to_vec()).as_ref()).unwrap();
+            }
+        }
+        assert!((spread.entropy() - 2.0).abs() < 1e-12);
+    }
+
+    #[quickcheck]
+    fn mode_count(orders: ChainDense) -> bool {
+        match orders.mode() {
+            None => orders.is_empty() && orders.mode_count() == 0,
+            Some(mode) => {
+                let count = orders.iter().filter(|o| o.order == mode.as_ref().order).count();
+                count == orders.mode_count()
+                    && orders
+                        .iter()
+                        .all(|a| orders.iter().filter(|b| a.order == b.order).count() <= count)
+            }
+        }
+    }
+
+    #[test]
+    fn mode_plurality() {
+        let mut orders = ChainDense::new(3);
+        for order in [[0, 1, 2], [2, 1, 0], [1, 0, 2], [2, 1, 0], [0, 1, 2], [2, 1, 0]] {
+            orders.push(Chain::new(order.to_vec()).as_ref()).unwrap();
+        }
+        assert_eq!(orders.mode(), Some(Chain::new(vec![2, 1, 0])));
+        assert_eq!(orders.mode_count(), 3);
+
+        // Tie broken by first occurrence
+        orders.truncate(5);
+        assert_eq!(orders.mode(), Some(Chain::new(vec![0, 1, 2])));
+        assert_eq!(ChainDense::new(3).mode(), None);
+    }
+
+    #[quickcheck]
+    fn as_slice_rows(orders: ChainDense) -> bool {
+        let slice = orders.as_slice();
+        let n = orders.elements();
+        slice.len() == orders.len() * n
+            && orders.iter().enumerate().all(|(i, o)| o.order == &slice[(i * n)..((i + 1) * n)])
+    }
+
+    #[quickcheck]
+    fn add_remove_element(orders: ChainDense) -> bool {
+        let mut added = orders.clone();
+        let new = added.add_element();
+        let last = added.iter().all(|o| o.order.last() == Some(&new));
+        added.remove_element(new).unwrap();
+        last && added == orders
+    }
+
+    #[quickcheck]
+    fn remove_element(orders: ChainDense, target: usize) -> bool {
+        if orders.elements == 0 {
+            return true;
+        }
+        let target = target % orders.elements;
+        let mut removed = orders.clone();
+        removed.remove_element(target).unwrap();
+        let expected = |o: ChainRef| -> Vec<usize> {
+            o.order.iter().filter(|&&e| e != target).map(|&e| e - usize::from(e > target)).collect()
+        };
+        valid(&removed)
+            && removed.len() == if orders.elements == 1 { 0 } else { orders.len() }
+            && removed.iter().zip(orders.iter()).all(|(new, old)| new.order == expected(old))
+    }
+
+    #[quickcheck]
+    fn project_all(orders: ChainDense) -> bool {
+        let all: Vec<usize> = (0..orders.elements).collect();
+        orders.project(&all) == Ok(orders.clone())
+            && orders.project(&[0, 0]).is_err()
+            && orders.project(&[orders.elements]).is_err()
+    }
+
+    #[quickcheck]
+    fn project_remove(orders: ChainDense, target: usize) -> bool {
+        if orders.elements < 2 {
+            return true;
+        }
+        let target = target % orders.elements;
+        let keep: Vec<usize> = (0..orders.elements).filter(|&c| c != target).collect();
+        let mut removed = orders.clone();
+        removed.remove_element(target).unwrap();
+        orders.project(&keep) == Ok(removed)
+    }
+
+    #[quickcheck]
+    fn relabel_inverse(mut orders: ChainDense, seed: u64) -> bool {
+        let before = orders.clone();
+        let identity: Vec<usize> = (0..orders.elements).collect();
+        orders.relabel(&identity).unwrap();
+        if orders != before {
+            return false;
+        }
+        if orders.relabel(&[0; 2]).is_ok() {
+            return false;
+        }
+        let perm = Chain::random(&mut StdRng::seed_from_u64(seed), orders.elements);
+        let mut inverse = vec![0; perm.elements()];
+        for (i, &p) in perm.order.iter().enumerate() {
+            inverse[p] = i;
+        }
+        orders.relabel(&perm.order).unwrap();
+        let relabeled = valid(&orders);
+        orders.relabel(&inverse).unwrap();
+        relabeled && orders == before
+    }
+
+    #[quickcheck]
+    fn sample(orders: ChainDense, k: usize) -> bool {
+        let mut rng = StdRng::seed_from_u64(0);
+        let k = k % (orders.len() + 2);
+        let sampled = orders.sample(&mut rng, k);
+        let k_replacement = if orders.is_empty() { 0 } else { k };
+        let with_replacement = orders.sample_with_replacement(&mut rng, k_replacement);
+        let mut originals: Vec<&[usize]> = orders.iter().map(|o| o.order).collect();
+        let members =
+            sampled.iter().chain(with_replacement.iter()).all(|o| originals.contains(&o.order));
+        // Every order should be used at most once when sampling without replacement
+        let mut unique = true;
+        for order in sampled.iter() {
+            match originals.iter().position(|o| *o == order.order) {
+                Some(i) => {
+                    originals.swap_remove(i);
+                }
+                None => unique = false,
+            }
+        }
+        valid(&sampled) && sampled.len() == k.min(orders.len()) && members && unique
+    }
+
+    #[quickcheck]
+    fn memory_usage(orders: ChainDense) -> bool {
+        let used = orders.len() * orders.elements * size_of::<usize>();
+        let mut reserved = orders.clone();
+        reserved.orders.reserve(100);
+        used <= orders.memory_usage() && orders.memory_usage() < reserved.memory_usage()
+    }
+
+    #[cfg(feature = "rayon")]
+    #[quickcheck]
+    fn generate_par(orders: ChainDense, new_orders: u16, seed: u64) -> bool {
+        let new_orders = new_orders as usize % (3 * PAR_CHUNK);
+        let mut a = orders.clone();
+        let mut b = orders;
+        a.generate_uniform_par(&mut StdRng::seed_from_u64(seed), new_orders);
+        b.generate_uniform_par(&mut StdRng::seed_from_u64(seed), new_orders);
+        valid(&a) && a == b
+    }
+
+    #[bench]
+    fn bench_generate_uniform(b: &mut test::Bencher) {
+        use rand::SeedableRng;
+        use rand_chacha::ChaCha12Rng;
+
+        let rng = ChaCha12Rng::from_seed([1; 32]);
+        b.iter(|| {
+            let mut rng = rng.clone();
+            let mut d = ChainDense::new(20);
+            d.generate_uniform(&mut rng, 100_000);
+        });
+    }
+
+    // Same seed and size as `bench_generate_uniform`, so the two can be
+    // compared directly.
+    #[cfg(feature = "rayon")]
+    #[bench]
+    fn bench_generate_uniform_par(b: &mut test::Bencher) {
+        use rand::SeedableRng;
+        use rand_chacha::ChaCha12Rng;
+
+        let rng = ChaCha12Rng::from_seed([1; 32]);
+        b.iter(|| {
+            let mut rng = rng.clone();
+            let mut d = ChainDense::new(20);
+            d.generate_uniform_par(&mut rng, 100_000);
+        });
+    }
+
+    // `remove_element` rewrites the orders in place, without allocating for
+    // each order.
+    #[bench]
+    fn bench_remove_element(b: &mut test::Bencher) {
+        let mut orders = ChainDense::new(100);
+        orders.generate_uniform(&mut StdRng::seed_from_u64(1), 10_000);
+        b.iter(|| {
+            let mut orders = orders.clone();
+            orders.remove_element(50).unwrap();
+            orders
+        });
+    }
+
+    #[test]
+    fn bradley_terry_converges() {
+        let known = [0.4, 0.3, 0.2, 0.1];
+        let games = 10_000.0;
+        let matrix: Vec<Vec<usize>> = known
+            .iter()
+            .map(|a| {
+                known
+                    .iter()
+                    .map(|b| if a == b { 0 } else { (games * a / (a + b)) as usize })
+                    .collect()
+            })
+            .collect();
+        let strengths = bradley_terry(&matrix, 1000);
+        assert!((strengths.iter().sum::<f64>() - 1.0).abs() < 1e-9);
+        for (s, k) in strengths.iter().zip(known) {
+            assert!((s - k).abs() < 1e-3);
+        }
+    }
+
+    #[test]
+    fn kendall_sum_best_ballot() {
+        let mut orders = ChainDense::new(3);
+        orders.push(ChainRef::new(&[0, 1, 2])).unwrap();
+        orders.push(ChainRef::new(&[0, 2, 1])).unwrap();
+        orders.push(ChainRef::new(&[2, 1, 0])).unwrap();
+        let sums: Vec<usize> = orders.iter().map(|o| orders.kendall_sum(o)).collect();
+        assert_eq!(sums, [4, 3, 5]);
+        let best = orders.iter().min_by_key(|o| orders.kendall_sum(*o)).unwrap();
+        assert_eq!(best.order, [0, 2, 1]);
+    }
+
+    #[quickcheck]
+    fn kendall_sum_matches_kemeny(orders: ChainDense) -> bool {
+        let (ranking, distance) = orders.kemeny();
+        orders.kendall_sum(ranking.as_ref()) == distance
+    }
+
+    #[test]
+    fn elimination_differs_from_borda() {
+        let mut orders = ChainDense::new(4);
+        for _ in 0..3 {
+            orders.push(ChainRef::new(&[0, 1, 2, 3])).unwrap();
+        }
+        for _ in 0..2 {
+            orders.push(ChainRef::new(&[1, 2, 3, 0])).unwrap();
+        }
+        // Borda ranks 1 highest, but 0 is the Condorcet winner.
+        assert_eq!(orders.borda(), [9, 12, 7, 2]);
+        assert_eq!(orders.baldwin().into_inner(), [0, 1, 2, 3]);
+        assert_eq!(orders.nanson().into_inner(), [0, 1, 2, 3]);
+    }
+
+    #[quickcheck]
+    fn elimination_condorcet(orders: ChainDense) -> bool {
+        let smith = orders.top_cycle();
+        if smith.len() != 1 {
+            return true;
+        }
+        orders.baldwin().order[0] == smith[0] && orders.nanson().order[0] == smith[0]
+    }
+
+    #[quickcheck]
+    fn to_owned_vec_round_trip(orders: ChainDense) -> bool {
+        let owned = orders.to_owned_vec();
+        let mut copy = ChainDense::new(orders.elements());
+        for order in &owned {
+            copy.push(order.as_ref()).unwrap();
+        }
+        owned.len() == orders.len() && copy == orders
+    }
+
+    #[quickcheck]
+    fn blend_boundaries(a: ChainDense, seed: u64) -> bool {
+        if a.elements() == 0 {
+            return true;
+        }
+        let mut rng = StdRng::seed_from_u64(seed);
+        let mut b = ChainDense::new(a.elements());
+        b.generate_uniform(&mut rng, 7);
+        let sorted = |orders: &ChainDense| {
+            let mut v: Vec<&[usize]> = orders.iter().map(|o| o.order).collect();
+            v.sort();
+            v.into_iter().flatten().copied().collect::<Vec<usize>>()
+        };
+        let only_a = ChainDense::blend(&mut rng, &a, &b, 1.0);
+        let only_b = ChainDense::blend(&mut rng, &a, &b, 0.0);
+        let half = ChainDense::blend(&mut rng, &a, &b, 0.5);
+        only_a.len() == a.len()
+            && sorted(&only_a) == sorted(&a)
+            && only_b.len() == b.len()
+            && sorted(&only_b) == sorted(&b)
+            && half.len() == (a.len() as f64 / 2.0).round() as usize + 4
+    }
+
+    #[quickcheck]
+    fn perturb_adjacent_distance(orders: ChainDense, swaps: u8, seed: u64) -> bool {
+        let mut perturbed = orders.clone();
+        perturbed.perturb_adjacent(&mut StdRng::seed_from_u64(seed), usize::from(swaps));
+        let distance: usize =
+            orders.iter().zip(perturbed.iter()).map(|(a, b)| a.kendall_distance(b)).sum();
+        valid(&perturbed) && perturbed.len() == orders.len() && distance <= usize::from(swaps)
+    }
+
+    #[quickcheck]
+    fn perturb_adjacent_zero(orders: ChainDense, seed: u64) -> bool {
+        let mut perturbed = orders.clone();
+        perturbed.perturb_adjacent(&mut StdRng::seed_from_u64(seed), 0);
+        perturbed == orders
+    }
+
+    #[quickcheck]
+    fn smith_borda_in_smith_set(orders: ChainDense) -> bool {
+        if orders.elements() == 0 {
+            return true;
+        }
+        orders.top_cycle().contains(&orders.smith_borda())
+    }
+
+    #[test]
+    fn smith_borda_condorcet_winner() {
+        let mut orders = ChainDense::new(4);
+        orders.push(ChainRef::new(&[3, 1, 0, 2])).unwrap();
+        orders.push(ChainRef::new(&[3, 1, 2, 0])).unwrap();
+        orders.push(ChainRef::new(&[1, 2, 0, 3])).unwrap();
+        // 3 beats every other element, but 1 has the highest Borda count
+        assert_eq!(orders.top_cycle(), [3]);
+        assert_eq!(orders.borda(), [2, 7, 3, 6]);
+        assert_eq!(orders.smith_borda(), 3);
+    }
+
+    #[test]
+    fn beats_tie() {
+        let mut orders = ChainDense::new(3);
+        orders.push(ChainRef::new(&[0, 1, 2])).unwrap();
+        orders.push(ChainRef::new(&[1, 2, 0])).unwrap();
+        assert_eq!(orders.beats(0, 1), Ordering::Equal);
+        assert_eq!(orders.beats(1, 0), Ordering::Equal);
+        assert!(!orders.supermajority(0, 1, 0.5));
+        assert!(orders.supermajority(1, 2, 2.0 / 3.0));
+        assert_eq!(orders.beats(1, 2), Ordering::Greater);
+    }
+
+    #[test]
+    #[should_panic]
+    fn beats_same_out_of_bounds() {
+        ChainDense::new(3).beats(3, 3);
+    }
+
+    #[test]
+    #[should_panic]
+    fn supermajority_same_out_of_bounds() {
+        ChainDense::new(3).supermajority(3, 3, 0.5);
+    }
+
+    #[quickcheck]
+    fn beats_matrix(orders: ChainDense, a: usize, b: usize) -> bool {
+        if orders.elements() == 0 {
+            return true;
+        }
+        let (a, b) = (a % orders.elements(), b % orders.elements());
+        let matrix = orders.pairwise_matrix();
+        orders.beats(a, b) == matrix[a][b].cmp(&matrix[b][a])
+            && orders.supermajority(a, b, 0.5) == (orders.beats(a, b) == Ordering::Greater)
+    }
+
+    #[quickcheck]
+    fn positional_score_borda(orders: ChainDense) -> bool {
+        let weights: Vec<usize> = (0..orders.elements()).rev().collect();
+        orders.positional_score(&weights) == orders.borda()
+    }
 }
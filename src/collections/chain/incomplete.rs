@@ -6,6 +6,7 @@ use rand::{
 use crate::{
     chain::ChainIRef,
     collections::{AddError, DenseOrders, chain::ChainDense},
+    specific::Specific,
 };
 
 /// Packed list of [`ChainI`](crate::chain::ChainI)
@@ -39,6 +40,46 @@ impl ChainIDense {
         ChainIDense { orders: Vec::new(), order_end: Vec::new(), elements }
     }
 
+    /// Create an empty collection which can hold `orders` orders without
+    /// reallocating, even if every order ranks all elements.
+    pub fn with_capacity(elements: usize, orders: usize) -> Self {
+        ChainIDense {
+            orders: Vec::with_capacity(orders * elements),
+            order_end: Vec::with_capacity(orders),
+            elements,
+        }
+    }
+
+    /// Shrink the capacity of the collection as much as possible.
+    pub fn shrink_to_fit(&mut self) {
+        self.orders.shrink_to_fit();
+        self.order_end.shrink_to_fit();
+    }
+
+    /// Remove every order, keeping the number of elements and the allocated
+    /// capacity.
+    pub fn clear(&mut self) {
+        self.orders.clear();
+        self.order_end.clear();
+    }
+
+    /// Keep only the first `len` orders, similar to [`Vec::truncate`]. Has no
+    /// effect if `len` is greater than or equal to the number of orders.
+    pub fn truncate(&mut self, len: usize) {
+        if len < self.len() {
+            let end = if len == 0 { 0 } else { self.order_end[len - 1] };
+            self.orders.truncate(end);
+            self.order_end.truncate(len);
+        }
+    }
+
+    /// Estimate of the number of bytes allocated on the heap by the
+    /// collection, based on the capacity of its buffers. Does not include the
+    /// size of the collection itself.
+    pub fn memory_usage(&self) -> usize {
+        (self.orders.capacity() + self.order_end.capacity()) * size_of::<usize>()
+    }
+
     pub fn elements(&self) -> usize {
         self.elements
     }
@@ -46,6 +87,246 @@ impl ChainIDense {
     pub fn iter(&self) -> impl Iterator<Item = ChainIRef<'_>> {
         (0..self.len()).map(|i| self.get(i))
     }
+
+    /// Move all orders from `other` to the end of `self`, leaving `other`
+    /// empty.
+    ///
+    /// Returns `Err` if the collections have a different number of elements.
+    pub fn append(&mut self, other: &mut ChainIDense) -> Result<(), AddError> {
+        if self.elements != other.elements {
+            return Err(AddError::Elements);
+        }
+        self.orders.try_reserve(other.orders.len()).or(Err(AddError::Alloc))?;
+        self.order_end.try_reserve(other.order_end.len()).or(Err(AddError::Alloc))?;
+        let offset = self.orders.len();
+        self.orders.append(&mut other.orders);
+        self.order_end.extend(other.order_end.drain(..).map(|end| end + offset));
+        Ok(())
+    }
+
+    /// Split the collection in two at index `at`, similar to
+    /// [`Vec::split_off`]. Returns the orders `[at, len)`, and `self` keeps
+    /// the orders `[0, at)`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `at > len`.
+    #[must_use]
+    pub fn split_off(&mut self, at: usize) -> ChainIDense {
+        assert!(at <= self.len());
+        let offset = if at == 0 { 0 } else { self.order_end[at - 1] };
+        let orders = self.orders.split_off(offset);
+        let mut order_end = self.order_end.split_off(at);
+        for end in &mut order_end {
+            *end -= offset;
+        }
+        ChainIDense { orders, order_end, elements: self.elements }
+    }
+
+    /// Keep only the `k` highest ranked elements of every order. Orders
+    /// ranking at most `k` elements are not changed.
+    pub fn truncate_ballots(&mut self, k: usize) {
+        let mut start = 0;
+        let mut new_end = 0;
+        for i in 0..self.len() {
+            let end = self.order_end[i];
+            let len = (end - start).min(k);
+            self.orders.copy_within(start..(start + len), new_end);
+            new_end += len;
+            start = end;
+            self.order_end[i] = new_end;
+        }
+        self.orders.truncate(new_end);
+    }
+
+    /// Returns the number of orders which don't rank every element, and the
+    /// mean number of ranked elements per order. The mean is 0 if the
+    /// collection is empty.
+    ///
+    /// ```
+    /// use orders::{
+    ///     chain::ChainIRef,
+    ///     collections::{ChainIDense, DenseOrders},
+    /// };
+    ///
+    /// let mut orders = ChainIDense::new(3);
+    /// orders.push(ChainIRef::new(3, &[2, 0, 1])).unwrap();
+    /// orders.push(ChainIRef::new(3, &[1])).unwrap();
+    /// orders.push(ChainIRef::new(3, &[])).unwrap();
+    /// orders.push(ChainIRef::new(3, &[0, 2])).unwrap();
+    /// assert_eq!(orders.exhaustion_stats(), (3, 1.5));
+    /// ```
+    pub fn exhaustion_stats(&self) -> (usize, f64) {
+        let exhausted = self.iter().filter(|order| order.len() < self.elements).count();
+        let mean = if self.is_empty() { 0.0 } else { self.orders.len() as f64 / self.len() as f64 };
+        (exhausted, mean)
+    }
+
+    /// Returns a matrix `m`, where `m[a][b]` is the number of orders ranking
+    /// `a` higher than `b`. An element ranked by an order is higher than every
+    /// element the order doesn't rank, while two unranked elements count
+    /// towards neither `m[a][b]` nor `m[b][a]`.
+    ///
+    /// ```
+    /// use orders::{
+    ///     chain::ChainIRef,
+    ///     collections::{ChainIDense, DenseOrders},
+    /// };
+    ///
+    /// let mut orders = ChainIDense::new(4);
+    /// orders.push(ChainIRef::new(4, &[2, 0])).unwrap();
+    /// let m = orders.pairwise_matrix();
+    /// assert_eq!(m[2][0], 1);
+    /// assert_eq!(m[0][3], 1);
+    /// assert_eq!((m[1][3], m[3][1]), (0, 0));
+    /// ```
+    pub fn pairwise_matrix(&self) -> Vec<Vec<usize>> {
+        let mut matrix = vec![vec![0; self.elements]; self.elements];
+        let mut ranked = vec![false; self.elements];
+        for order in self.iter() {
+            ranked.fill(false);
+            for &c in order.order {
+                ranked[c] = true;
+            }
+            for (i, &a) in order.order.iter().enumerate() {
+                for &b in &order.order[(i + 1)..] {
+                    matrix[a][b] += 1;
+                }
+                for (b, _) in ranked.iter().enumerate().filter(|(_, r)| !**r) {
+                    matrix[a][b] += 1;
+                }
+            }
+        }
+        matrix
+    }
+
+    /// Complete every order by ranking its unranked elements after the ranked
+    /// ones, in a uniformly random order. Empty orders become uniformly random
+    /// permutations. See [`ChainIRef::complete_random`].
+    pub fn complete_random<R: rand::Rng>(&self, rng: &mut R) -> ChainDense {
+        let mut out = ChainDense::with_capacity(self.elements, self.len());
+        let mut ranked = vec![false; self.elements];
+        for order in self.iter() {
+            ranked.fill(false);
+            for &c in order.order {
+                ranked[c] = true;
+            }
+            let start = out.orders.len() + order.len();
+            out.orders.extend_from_slice(order.order);
+            out.orders.extend((0..self.elements).filter(|&c| !ranked[c]));
+            out.orders[start..].shuffle(rng);
+        }
+        out
+    }
+
+    /// Elect `seats` elements using [single transferable
+    /// vote](https://en.wikipedia.org/wiki/Single_transferable_vote), returning
+    /// them in the order they were elected.
+    ///
+    /// Each round, every order counts towards its highest ranked element which
+    /// is neither elected nor eliminated; orders with no such element are
+    /// exhausted. If an element reaches the Droop quota, `len / (seats + 1) +
+    /// 1`, then it is elected and its surplus is transferred using the Gregory
+    /// method: every order counting towards it keeps a fraction `surplus /
+    /// votes` of its weight. Otherwise the element with the fewest votes is
+    /// eliminated. Ties are broken in favor of lower indices.
+    ///
+    /// If `seats` is larger than the number of elements, then every element is
+    /// elected.
+    pub fn stv(&self, seats: usize) -> Vec<usize> {
+        let seats = seats.min(self.elements);
+        let quota = (self.len() / (seats + 1) + 1) as f64;
+        let mut weights = vec![1.0; self.len()];
+        let mut continuing = vec![true; self.elements];
+        let mut elected = Vec::with_capacity(seats);
+        let mut votes: Vec<f64> = vec![0.0; self.elements];
+        while elected.len() < seats {
+            votes.fill(0.0);
+            for (order, w) in self.iter().zip(&weights) {
+                if let Some(&c) = order.order.iter().find(|&&c| continuing[c]) {
+                    votes[c] += w;
+                }
+            }
+            let mut remaining: Vec<usize> = (0..self.elements).filter(|&c| continuing[c]).collect();
+            if remaining.len() <= seats - elected.len() {
+                remaining.sort_by(|&a, &b| votes[b].total_cmp(&votes[a]));
+                elected.extend(remaining);
+                break;
+            }
+            let by_votes = |a: &usize, b: &usize| votes[*a].total_cmp(&votes[*b]).then(b.cmp(a));
+            let best = remaining.iter().copied().max_by(by_votes).unwrap();
+            if votes[best] >= quota {
+                let factor = (votes[best] - quota) / votes[best];
+                for (order, w) in self.iter().zip(&mut weights) {
+                    if order.order.iter().find(|&&c| continuing[c]) == Some(&best) {
+                        *w *= factor;
+                    }
+                }
+                continuing[best] = false;
+                elected.push(best);
+            } else {
+                let worst = remaining.iter().copied().min_by(by_votes).unwrap();
+                continuing[worst] = false;
+            }
+        }
+        elected
+    }
+
+    /// Winner by [Bucklin voting](https://en.wikipedia.org/wiki/Bucklin_voting).
+    /// See [`ChainIDense::bucklin_round`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if there are no elements.
+    pub fn bucklin(&self) -> Specific {
+        self.bucklin_round().0
+    }
+
+    /// Winner by [Bucklin voting](https://en.wikipedia.org/wiki/Bucklin_voting),
+    /// together with the round the winner was found in, starting from 1.
+    ///
+    /// In round `r`, every order adds a vote to its `r`th ranked element, if
+    /// it ranks that many elements. Once an element has votes from more than
+    /// half of the orders, the element with the most votes wins. If no element
+    /// reaches a majority before every order is exhausted, then the element
+    /// with the most votes wins. Ties are broken in favor of lower indices.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there are no elements.
+    ///
+    /// ```
+    /// use orders::{
+    ///     chain::ChainIRef,
+    ///     collections::{ChainIDense, DenseOrders},
+    /// };
+    ///
+    /// let mut orders = ChainIDense::new(3);
+    /// for order in [&[0, 1][..], &[1, 0], &[2, 1], &[0, 2], &[1, 2]] {
+    ///     orders.push(ChainIRef::new(3, order)).unwrap();
+    /// }
+    /// let (winner, round) = orders.bucklin_round();
+    /// assert_eq!(winner.value(), 1);
+    /// assert_eq!(round, 2);
+    /// ```
+    pub fn bucklin_round(&self) -> (Specific, usize) {
+        assert!(self.elements != 0);
+        let longest = self.iter().map(|order| order.len()).max().unwrap_or(0);
+        let mut votes = vec![0; self.elements];
+        let mut round = 0;
+        loop {
+            for order in self.iter() {
+                if let Some(&c) = order.order.get(round) {
+                    votes[c] += 1;
+                }
+            }
+            round += 1;
+            let (best, &most) = votes.iter().enumerate().rev().max_by_key(|&(_, v)| v).unwrap();
+            if most * 2 > self.len() || round >= longest {
+                return (Specific::new(best, self.elements), round);
+            }
+        }
+    }
 }
 
 impl<'a> DenseOrders<'a> for ChainIDense {
@@ -80,8 +361,26 @@ impl<'a> DenseOrders<'a> for ChainIDense {
         Ok(())
     }
 
-    fn remove_element(&mut self, _target: usize) -> Result<(), &'static str> {
-        todo!();
+    fn remove_element(&mut self, target: usize) -> Result<(), &'static str> {
+        if self.elements <= target {
+            return Err("Element not in collection");
+        }
+        let mut written = 0;
+        let mut start = 0;
+        for end in &mut self.order_end {
+            for i in start..*end {
+                let el = self.orders[i];
+                if el != target {
+                    self.orders[written] = if el > target { el - 1 } else { el };
+                    written += 1;
+                }
+            }
+            start = *end;
+            *end = written;
+        }
+        self.orders.truncate(written);
+        self.elements -= 1;
+        Ok(())
     }
 
     fn generate_uniform<R: rand::Rng>(&mut self, rng: &mut R, new_orders: usize) {
@@ -111,15 +410,42 @@ impl From<ChainDense> for ChainIDense {
     }
 }
 
+impl TryFrom<ChainIDense> for ChainDense {
+    type Error = usize;
+
+    /// Convert to a collection of complete orders, reusing the buffer of
+    /// `value`.
+    ///
+    /// Returns `Err(i)` if order `i` is the first order which doesn't rank
+    /// every element. A collection of zero elements can only be converted if
+    /// it's empty.
+    fn try_from(value: ChainIDense) -> Result<Self, Self::Error> {
+        let elements = value.elements;
+        if elements == 0 {
+            return if value.is_empty() { Ok(ChainDense::new(0)) } else { Err(0) };
+        }
+        let mut start = 0;
+        for (i, &end) in value.order_end.iter().enumerate() {
+            if end - start != elements {
+                return Err(i);
+            }
+            start = end;
+        }
+        Ok(ChainDense { orders: value.orders, elements })
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use quickcheck::{Arbitrary, Gen};
+    use rand::{SeedableRng, rngs::StdRng};
 
     use super::*;
     use crate::{
         OrderOwned, OrderRef,
         chain::ChainI,
-        tests::{BoundedArbitrary, std_rng},
+        tests::{BoundedArbitrary, shrink_dense, std_rng},
+        unique_and_bounded,
     };
 
     /// Returns true if this struct is in a valid state, used for debugging.
@@ -154,6 +480,12 @@ mod tests {
             orders.generate_uniform(&mut std_rng(g), orders_count);
             orders
         }
+
+        fn shrink(&self) -> Box<dyn Iterator<Item = Self>> {
+            shrink_dense(self, self.len(), self.elements, Self::truncate, |o, e| {
+                o.remove_element(e)
+            })
+        }
     }
 
     #[quickcheck]
@@ -161,6 +493,154 @@ mod tests {
         valid(&orders)
     }
 
+    #[quickcheck]
+    fn shrink_valid(orders: ChainIDense) -> bool {
+        orders.shrink().all(|o| valid(&o))
+    }
+
+    #[quickcheck]
+    fn remove_element(orders: ChainIDense, target: usize) -> bool {
+        if orders.elements == 0 {
+            return true;
+        }
+        let target = target % orders.elements;
+        let mut removed = orders.clone();
+        removed.remove_element(target).unwrap();
+        let expected = |o: ChainIRef| -> Vec<usize> {
+            o.order.iter().filter(|&&e| e != target).map(|&e| e - usize::from(e > target)).collect()
+        };
+        valid(&removed)
+            && removed.elements == orders.elements - 1
+            && removed.len() == orders.len()
+            && removed.iter().zip(orders.iter()).all(|(new, old)| new.order == expected(old))
+    }
+
+    // The example from the Wikipedia article on single transferable vote, with
+    // the candidates Oranges, Pears, Chocolate, Strawberries and Hamburgers.
+    #[test]
+    fn stv_example() {
+        let ballots: [(usize, &[usize]); 6] =
+            [(4, &[0]), (2, &[1, 0]), (8, &[2, 3]), (4, &[2, 4]), (1, &[3]), (1, &[4])];
+        let mut orders = ChainIDense::new(5);
+        for (count, order) in ballots {
+            for _ in 0..count {
+                orders.push(ChainIRef::new(5, order)).unwrap();
+            }
+        }
+        assert_eq!(orders.stv(3), [2, 0, 3]);
+    }
+
+    #[quickcheck]
+    fn stv_seats(orders: ChainIDense, seats: usize, huge: bool) -> bool {
+        let seats = if huge { usize::MAX - seats } else { seats % (orders.elements + 2) };
+        let mut elected = orders.stv(seats);
+        let len = elected.len();
+        elected.sort();
+        elected.dedup();
+        len == seats.min(orders.elements) && elected.len() == len
+    }
+
+    #[quickcheck]
+    fn append_push(mut a: ChainIDense, new_orders: u8) -> bool {
+        if a.append(&mut ChainIDense::new(a.elements + 1)).is_ok() {
+            return false;
+        }
+        let mut b = ChainIDense::new(a.elements);
+        if a.elements != 0 {
+            b.generate_uniform(&mut StdRng::seed_from_u64(0), new_orders as usize);
+        }
+        let mut pushed = a.clone();
+        for order in b.iter() {
+            pushed.push(order).unwrap();
+        }
+        a.append(&mut b).unwrap();
+        valid(&a) && a.orders == pushed.orders && a.order_end == pushed.order_end && b.is_empty()
+    }
+
+    #[quickcheck]
+    fn split_off(orders: ChainIDense, at: usize) -> bool {
+        let at = at % (orders.len() + 1);
+        let mut a = orders.clone();
+        let b = a.split_off(at);
+        valid(&a)
+            && valid(&b)
+            && a.len() == at
+            && b.len() == orders.len() - at
+            && a.iter().chain(b.iter()).zip(orders.iter()).all(|(x, y)| x == y)
+    }
+
+    #[quickcheck]
+    fn truncate_clear(orders: ChainIDense, len: usize) -> bool {
+        let len = len % (orders.len() + 2);
+        let mut truncated = orders.clone();
+        truncated.truncate(len);
+        let prefix = valid(&truncated)
+            && truncated.len() == len.min(orders.len())
+            && truncated.iter().zip(orders.iter()).all(|(a, b)| a == b);
+        truncated.clear();
+        prefix && truncated.is_empty() && truncated.elements() == orders.elements()
+    }
+
+    #[quickcheck]
+    fn truncate_ballots(orders: ChainIDense, k: usize) -> bool {
+        let k = k % (orders.elements + 1);
+        let mut truncated = orders.clone();
+        truncated.truncate_ballots(k);
+        valid(&truncated)
+            && truncated.len() == orders.len()
+            && truncated
+                .iter()
+                .zip(orders.iter())
+                .all(|(t, o)| t.len() == o.len().min(k) && o.order.starts_with(t.order))
+    }
+
+    #[quickcheck]
+    fn pairwise_matrix_complete(orders: ChainIDense, seed: u64) -> bool {
+        // Completed orders agree on every pair involving a ranked element
+        let complete = orders.complete_random(&mut StdRng::seed_from_u64(seed));
+        let a = orders.pairwise_matrix();
+        let b = complete.pairwise_matrix();
+        let ranked: Vec<Vec<bool>> = orders
+            .iter()
+            .map(|o| (0..orders.elements).map(|c| o.order.contains(&c)).collect())
+            .collect();
+        let both_unranked = |x: usize, y: usize| ranked.iter().filter(|r| !r[x] && !r[y]).count();
+        (0..orders.elements).all(|x| {
+            (0..orders.elements).all(|y| {
+                let total = if x == y { 0 } else { orders.len() - both_unranked(x, y) };
+                a[x][y] + a[y][x] == total && a[x][y] <= b[x][y]
+            })
+        })
+    }
+
+    #[quickcheck]
+    fn complete_round_trip(orders: ChainDense) -> bool {
+        let incomplete = ChainIDense::from(orders.clone());
+        ChainDense::try_from(incomplete) == Ok(orders)
+    }
+
+    #[test]
+    fn try_from_incomplete() {
+        let mut orders = ChainIDense::new(3);
+        orders.push(ChainIRef::new(3, &[2, 0, 1])).unwrap();
+        orders.push(ChainIRef::new(3, &[1, 2])).unwrap();
+        orders.push(ChainIRef::new(3, &[0, 1, 2])).unwrap();
+        assert_eq!(ChainDense::try_from(orders.clone()), Err(1));
+        orders.truncate(1);
+        let complete = ChainDense::try_from(orders).unwrap();
+        assert_eq!(complete.as_slice(), [2, 0, 1]);
+    }
+
+    #[quickcheck]
+    fn complete_random(orders: ChainIDense, seed: u64) -> bool {
+        let complete = orders.complete_random(&mut StdRng::seed_from_u64(seed));
+        complete.elements() == orders.elements
+            && complete.len() == orders.len()
+            && complete.iter().zip(orders.iter()).all(|(c, o)| {
+                unique_and_bounded(orders.elements, c.order) && c.order.starts_with(o.order)
+            })
+    }
+
     #[quickcheck]
     fn iter_collect(orders: ChainIDense) -> bool {
         let orig = orders.clone();
@@ -172,4 +652,39 @@ mod tests {
         }
         true
     }
+
+    #[test]
+    fn bucklin_exhausted() {
+        let mut orders = ChainIDense::new(4);
+        for order in [&[2][..], &[1], &[3], &[1, 0]] {
+            orders.push(ChainIRef::new(4, order)).unwrap();
+        }
+        let (winner, round) = orders.bucklin_round();
+        assert_eq!((winner.value(), round), (1, 2));
+        assert_eq!(ChainIDense::new(2).bucklin_round().0.value(), 0);
+    }
+
+    #[quickcheck]
+    fn bucklin_first_round_majority(orders: ChainIDense) -> bool {
+        if orders.elements() == 0 {
+            return true;
+        }
+        let mut first = vec![0; orders.elements()];
+        for order in orders.iter().filter(|o| !o.is_empty()) {
+            first[order.winner()] += 1;
+        }
+        let (winner, round) = orders.bucklin_round();
+        match first.iter().position(|&v| v * 2 > orders.len()) {
+            Some(c) => winner.value() == c && round == 1,
+            None => round > 1 || orders.iter().all(|o| o.len() <= 1),
+        }
+    }
+
+    #[quickcheck]
+    fn exhaustion_stats_complete(orders: ChainDense) -> bool {
+        let len = orders.len();
+        let elements = orders.elements();
+        let (exhausted, mean) = ChainIDense::from(orders).exhaustion_stats();
+        exhausted == 0 && (len == 0 || mean == elements as f64)
+    }
 }
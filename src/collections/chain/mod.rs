@@ -1,5 +1,5 @@
 mod complete;
 mod incomplete;
 
-pub use complete::ChainDense;
+pub use complete::{ChainDense, KEMENY_EXACT_MAX, bradley_terry, pairwise_agreement};
 pub use incomplete::ChainIDense;
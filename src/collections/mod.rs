@@ -6,13 +6,20 @@ mod tied;
 
 pub use binary::BinaryDense;
 pub use cardinal::CardinalDense;
-pub use chain::{ChainDense, ChainIDense};
+pub use chain::{ChainDense, ChainIDense, KEMENY_EXACT_MAX, bradley_terry, pairwise_agreement};
 use rand::Rng;
 pub use specific::SpecificDense;
-pub use tied::{TiedDense, TiedIDense};
+pub use tied::{TiedDense, TiedIDense, UnrankedPolicy};
 
 use crate::OrderRef;
 
+/// Number of orders generated by each task in the parallel
+/// `generate_uniform_par` methods. Every chunk gets its own rng seeded from the
+/// supplied rng, so the result only depends on the seed and not on the number
+/// of threads.
+#[cfg(feature = "rayon")]
+pub(crate) const PAR_CHUNK: usize = 1024;
+
 // Lifetime needed because `Order` may be a reference which then needs a
 // lifetime
 pub trait DenseOrders<'a> {
@@ -34,6 +41,16 @@ pub trait DenseOrders<'a> {
         self.try_get(i).unwrap()
     }
 
+    /// Returns every order as an owned value, in the same order as the
+    /// collection.
+    ///
+    /// Every order gets its own allocation, so this loses the memory
+    /// advantage of the packed storage. Mostly useful for debugging and
+    /// tests.
+    fn to_owned_vec(&'a self) -> Vec<<Self::Order as OrderRef>::Owned> {
+        (0..self.len()).map(|i| self.get(i).to_owned()).collect()
+    }
+
     /// Removes element from the orders, offsetting the other elements to
     /// take their place.
     fn remove_element(&mut self, target: usize) -> Result<(), &'static str>;
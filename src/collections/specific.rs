@@ -42,6 +42,36 @@ impl SpecificDense {
         SpecificDense { orders: Vec::new(), elements }
     }
 
+    /// Create an empty collection which can hold `orders` orders without
+    /// reallocating.
+    pub fn with_capacity(elements: usize, orders: usize) -> Self {
+        SpecificDense { orders: Vec::with_capacity(orders), elements }
+    }
+
+    /// Shrink the capacity of the collection as much as possible.
+    pub fn shrink_to_fit(&mut self) {
+        self.orders.shrink_to_fit();
+    }
+
+    /// Remove every order, keeping the number of elements and the allocated
+    /// capacity.
+    pub fn clear(&mut self) {
+        self.orders.clear();
+    }
+
+    /// Keep only the first `len` orders, similar to [`Vec::truncate`]. Has no
+    /// effect if `len` is greater than or equal to the number of orders.
+    pub fn truncate(&mut self, len: usize) {
+        self.orders.truncate(len);
+    }
+
+    /// Estimate of the number of bytes allocated on the heap by the
+    /// collection, based on the capacity of its buffers. Does not include the
+    /// size of the collection itself.
+    pub fn memory_usage(&self) -> usize {
+        self.orders.capacity() * size_of::<usize>()
+    }
+
     /// Create a `SpecificDense` from a list of elements.
     ///
     /// # Panics
@@ -162,9 +192,10 @@ impl FromIterator<Specific> for Option<SpecificDense> {
 #[cfg(test)]
 mod tests {
     use quickcheck::{Arbitrary, Gen};
+    use rand::{SeedableRng, rngs::StdRng};
 
     use super::*;
-    use crate::tests::{BoundedArbitrary, std_rng};
+    use crate::tests::{BoundedArbitrary, shrink_dense, std_rng};
 
     // Checks if all invariants of the format are valid, used in tests
     fn valid(sd: &SpecificDense) -> bool {
@@ -187,6 +218,12 @@ mod tests {
             orders.generate_uniform(&mut std_rng(g), orders_count);
             orders
         }
+
+        fn shrink(&self) -> Box<dyn Iterator<Item = Self>> {
+            shrink_dense(self, self.len(), self.elements, Self::truncate, |o, e| {
+                o.remove_element(e)
+            })
+        }
     }
 
     #[quickcheck]
@@ -194,6 +231,19 @@ mod tests {
         valid(&orders)
     }
 
+    #[quickcheck]
+    fn shrink_valid(orders: SpecificDense) -> bool {
+        orders.shrink().all(|o| valid(&o))
+    }
+
+    #[quickcheck]
+    fn generate_uniform_len(mut orders: SpecificDense, new_orders: u8, seed: u64) -> bool {
+        let len = orders.len();
+        orders.generate_uniform(&mut StdRng::seed_from_u64(seed), new_orders as usize);
+        let expected = if orders.elements == 0 { len } else { len + new_orders as usize };
+        valid(&orders) && orders.len() == expected
+    }
+
     #[quickcheck]
     fn majority_bound(orders: SpecificDense) -> bool {
         match orders.majority() {
@@ -5,9 +5,13 @@ use rand::{
     seq::SliceRandom,
 };
 
+#[cfg(feature = "rayon")]
+use crate::collections::PAR_CHUNK;
 use crate::{
-    collections::{AddError, CardinalDense, ChainDense, DenseOrders, SpecificDense},
-    orders::tied::TiedRef,
+    Order, OrderRef,
+    collections::{AddError, BinaryDense, CardinalDense, ChainDense, DenseOrders, SpecificDense},
+    distribution::OrderDistribution,
+    orders::tied::{Tied, TiedRef},
 };
 
 /// Packed list of [`Tied`](crate::tied::Tied)
@@ -39,10 +43,143 @@ impl TiedDense {
         TiedDense { orders: Vec::new(), ties: Vec::new(), elements }
     }
 
+    /// Create an empty collection which can hold `orders` orders without
+    /// reallocating.
+    pub fn with_capacity(elements: usize, orders: usize) -> Self {
+        TiedDense {
+            orders: Vec::with_capacity(orders * elements),
+            ties: Vec::with_capacity(orders * elements.saturating_sub(1)),
+            elements,
+        }
+    }
+
+    /// Shrink the capacity of the collection as much as possible.
+    pub fn shrink_to_fit(&mut self) {
+        self.orders.shrink_to_fit();
+        self.ties.shrink_to_fit();
+    }
+
+    /// Remove every order, keeping the number of elements and the allocated
+    /// capacity.
+    pub fn clear(&mut self) {
+        self.orders.clear();
+        self.ties.clear();
+    }
+
+    /// Keep only the first `len` orders, similar to [`Vec::truncate`]. Has no
+    /// effect if `len` is greater than or equal to the number of orders.
+    pub fn truncate(&mut self, len: usize) {
+        self.orders.truncate(len * self.elements);
+        self.ties.truncate(len * self.elements.saturating_sub(1));
+    }
+
+    /// Add a new element, ranked last and not tied in every order, and return
+    /// it. This is the inverse of [`DenseOrders::remove_element`] with the
+    /// returned element.
+    pub fn add_element(&mut self) -> usize {
+        let new = self.elements;
+        let len = self.len();
+        self.elements += 1;
+        self.orders.resize(len * self.elements, new);
+        // Orders with a single element have no ties to move
+        let old_ties = new.saturating_sub(1);
+        self.ties.resize(len * new, false);
+        for i in (0..len).rev() {
+            self.orders.copy_within((i * new)..((i + 1) * new), i * self.elements);
+            self.orders[i * self.elements + new] = new;
+            self.ties.copy_within((i * old_ties)..((i + 1) * old_ties), i * new);
+            self.ties[i * new + old_ties] = false;
+        }
+        new
+    }
+
+    /// Remove and return the order at index `i`, replacing it with the last
+    /// order. This changes the order of the collection, but takes `O(elements)`
+    /// time. Returns `None` if `i` is out of bounds.
+    pub fn swap_remove(&mut self, i: usize) -> Option<Tied> {
+        if i >= self.len() {
+            return None;
+        }
+        let removed = self.get(i).to_owned();
+        let last = self.len() - 1;
+        let tie_width = self.elements - 1;
+        self.orders.copy_within((last * self.elements).., i * self.elements);
+        self.orders.truncate(last * self.elements);
+        self.ties.copy_within((last * tie_width).., i * tie_width);
+        self.ties.truncate(last * tie_width);
+        Some(removed)
+    }
+
+    /// Estimate of the number of bytes allocated on the heap by the
+    /// collection, based on the capacity of its buffers. Does not include the
+    /// size of the collection itself.
+    pub fn memory_usage(&self) -> usize {
+        self.orders.capacity() * size_of::<usize>() + self.ties.capacity() * size_of::<bool>()
+    }
+
     pub fn iter(&self) -> impl Iterator<Item = TiedRef<'_>> {
         (0..self.len()).map(|i| self.get(i))
     }
 
+    /// Move all orders from `other` to the end of `self`, leaving `other`
+    /// empty.
+    ///
+    /// Returns `Err` if the collections have a different number of elements.
+    pub fn append(&mut self, other: &mut TiedDense) -> Result<(), AddError> {
+        if self.elements != other.elements {
+            return Err(AddError::Elements);
+        }
+        self.orders.try_reserve(other.orders.len()).or(Err(AddError::Alloc))?;
+        self.ties.try_reserve(other.ties.len()).or(Err(AddError::Alloc))?;
+        self.orders.append(&mut other.orders);
+        self.ties.append(&mut other.ties);
+        Ok(())
+    }
+
+    /// Convert every order to a strict order, by ranking elements tied with
+    /// each other by ascending index.
+    ///
+    /// ```
+    /// use orders::{
+    ///     collections::{DenseOrders, TiedDense},
+    ///     tied::TiedRef,
+    /// };
+    ///
+    /// let mut orders = TiedDense::new(4);
+    /// orders.push(TiedRef::new(&[3, 2, 0, 1], &[true, false, true])).unwrap();
+    /// assert_eq!(orders.break_ties_by_index().as_slice(), [2, 3, 0, 1]);
+    /// ```
+    pub fn break_ties_by_index(self) -> ChainDense {
+        self.break_ties_with(|group| group.sort_unstable())
+    }
+
+    /// Convert every order to a strict order, by ranking elements tied with
+    /// each other in a uniformly random order, using random numbers from
+    /// `rng`.
+    pub fn break_ties_using<R: rand::Rng>(self, rng: &mut R) -> ChainDense {
+        self.break_ties_with(|group| group.shuffle(rng))
+    }
+
+    fn break_ties_with<F: FnMut(&mut [usize])>(mut self, mut f: F) -> ChainDense {
+        let elements = self.elements;
+        // With a single element there are no ties, so the loop is skipped
+        if elements > 1 {
+            let tie_width = elements - 1;
+            for (order, ties) in
+                self.orders.chunks_exact_mut(elements).zip(self.ties.chunks_exact(tie_width))
+            {
+                let mut start = 0;
+                for end in 1..=elements {
+                    if end == elements || !ties[end - 1] {
+                        f(&mut order[start..end]);
+                        start = end;
+                    }
+                }
+            }
+        }
+        ChainDense { orders: self.orders, elements }
+    }
+
     /// Pick a winning element from each ordering, randomly from their highest
     /// ranked (tied) elements.
     pub fn to_specific_using<R: rand::Rng>(self, rng: &mut R) -> SpecificDense {
@@ -53,6 +190,128 @@ impl TiedDense {
         orders.add_elements(orders.elements - elements);
         orders
     }
+
+    /// For every element, the probability that [`TiedDense::to_specific_using`]
+    /// picks it as the winner of a random order in the collection. Each order
+    /// splits its share equally among its highest ranked (tied) elements.
+    ///
+    /// Returns zeroes if the collection is empty.
+    pub fn winner_distribution(&self) -> Vec<f64> {
+        let mut res = vec![0.0; self.elements];
+        for order in self.iter() {
+            let winners = order.winners();
+            for &w in winners {
+                res[w] += 1.0 / winners.len() as f64;
+            }
+        }
+        if !self.is_empty() {
+            let len = self.len() as f64;
+            for r in &mut res {
+                *r /= len;
+            }
+        }
+        res
+    }
+
+    /// Sample and add `new_orders` orders from the distribution `dist`, using
+    /// random numbers from `rng`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `dist` generates orders of the wrong number of elements.
+    pub fn generate<R: rand::Rng, D: OrderDistribution<Tied>>(
+        &mut self,
+        rng: &mut R,
+        dist: &D,
+        new_orders: usize,
+    ) {
+        if self.elements == 0 {
+            return;
+        }
+        self.orders.reserve(self.elements * new_orders);
+        self.ties.reserve((self.elements - 1) * new_orders);
+        for _ in 0..new_orders {
+            let order = dist.sample(rng, self.elements);
+            assert!(order.elements() == self.elements);
+            self.orders.extend_from_slice(order.order());
+            self.ties.extend_from_slice(order.tied());
+        }
+    }
+
+    /// Sample and add `new_orders` random orders, uniformly among those where
+    /// `top` is the only highest ranked element, using random numbers from
+    /// `rng`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `top >= self.elements()`.
+    pub fn generate_uniform_with_winner<R: rand::Rng>(
+        &mut self,
+        rng: &mut R,
+        new_orders: usize,
+        top: usize,
+    ) {
+        assert!(top < self.elements);
+        let v: &mut [usize] = &mut (0..self.elements).collect::<Vec<usize>>();
+        v.swap(0, top);
+        self.orders.reserve(new_orders * self.elements);
+        self.ties.reserve(new_orders * (self.elements - 1));
+        let dist = Bernoulli::new(0.5).unwrap();
+        for _ in 0..new_orders {
+            v[1..].shuffle(rng);
+            self.orders.extend_from_slice(v);
+            if self.elements > 1 {
+                self.ties.push(false);
+                for _ in 0..(self.elements - 2) {
+                    let b = dist.sample(rng);
+                    self.ties.push(b);
+                }
+            }
+        }
+    }
+
+    /// Parallel version of [`DenseOrders::generate_uniform`].
+    ///
+    /// The orders are generated in chunks, each using its own rng seeded from
+    /// `rng`, so the result is the same for a given `rng` regardless of the
+    /// number of threads. It will not be the same as the result of
+    /// `generate_uniform`, but the orders follow the same distribution.
+    #[cfg(feature = "rayon")]
+    pub fn generate_uniform_par<R: rand::Rng>(&mut self, rng: &mut R, new_orders: usize) {
+        use rand::{SeedableRng, rngs::StdRng};
+        use rayon::prelude::*;
+
+        assert!(self.elements != 0 || new_orders == 0);
+        if self.elements == 0 || new_orders == 0 {
+            return;
+        }
+        if self.elements == 1 {
+            // There are no ties to generate
+            self.orders.resize(self.orders.len() + new_orders, 0);
+            return;
+        }
+        let elements = self.elements;
+        let rngs: Vec<StdRng> =
+            (0..new_orders.div_ceil(PAR_CHUNK)).map(|_| StdRng::from_rng(rng)).collect();
+        let orders_start = self.orders.len();
+        let ties_start = self.ties.len();
+        self.orders.resize(orders_start + elements * new_orders, 0);
+        self.ties.resize(ties_start + (elements - 1) * new_orders, false);
+        let orders_chunks = self.orders[orders_start..].par_chunks_mut(elements * PAR_CHUNK);
+        let ties_chunks = self.ties[ties_start..].par_chunks_mut((elements - 1) * PAR_CHUNK);
+        orders_chunks.zip(ties_chunks).zip(rngs).for_each(|((orders, ties), mut rng)| {
+            let dist = Bernoulli::new(0.5).unwrap();
+            for order in orders.chunks_exact_mut(elements) {
+                for (i, el) in order.iter_mut().enumerate() {
+                    *el = i;
+                }
+                order.shuffle(&mut rng);
+            }
+            for tie in ties {
+                *tie = dist.sample(&mut rng);
+            }
+        });
+    }
 }
 
 impl<'a> DenseOrders<'a> for TiedDense {
@@ -73,8 +332,8 @@ impl<'a> DenseOrders<'a> for TiedDense {
             return Err(AddError::Elements);
         }
 
-        self.orders.try_reserve(order.len() * self.elements).map_err(|_| AddError::Alloc)?;
-        self.ties.try_reserve(tie.len() * (self.elements - 1)).map_err(|_| AddError::Alloc)?;
+        self.orders.try_reserve(self.elements).map_err(|_| AddError::Alloc)?;
+        self.ties.try_reserve(self.elements.saturating_sub(1)).map_err(|_| AddError::Alloc)?;
 
         self.orders.extend_from_slice(order);
         self.ties.extend_from_slice(tie);
@@ -111,13 +370,13 @@ impl<'a> DenseOrders<'a> for TiedDense {
                 for j in 0..elements_old {
                     let el = self.orders[i * elements_old + j];
                     let out = match target.cmp(&el) {
-                        Ordering::Less => el,
+                        Ordering::Less => el - 1,
                         Ordering::Equal => {
                             debug_assert!(skipped.is_none());
                             skipped = Some(j);
                             continue;
                         }
-                        Ordering::Greater => el - 1,
+                        Ordering::Greater => el,
                     };
                     if skipped.is_none() {
                         self.orders[i * elements_new + j] = out;
@@ -136,11 +395,11 @@ impl<'a> DenseOrders<'a> for TiedDense {
                         self.ties.copy_within(start_old..(end_old - 1), start_new);
                     } else {
                         debug_assert!(0 < removed && removed < (elements_old - 1));
-                        // TODO: This may be wrong...
                         let pre = self.ties[start_old..end_old][removed - 1];
                         let next = self.ties[start_old..end_old][removed];
                         self.ties.copy_within(start_old..(start_old + removed - 1), start_new);
-                        self.ties.copy_within((start_old + removed)..end_old, start_new);
+                        self.ties
+                            .copy_within((start_old + removed + 1)..end_old, start_new + removed);
                         self.ties[start_new..end_new][removed - 1] = pre && next;
                     }
                 } else {
@@ -190,7 +449,7 @@ impl TryFrom<TiedDense> for CardinalDense {
         let max = value.elements - 1;
         let mut new_order = vec![0; value.elements];
         for order in value.iter() {
-            for (i, group) in order.iter_groups().enumerate() {
+            for (i, group) in order.iter_groups_ranked() {
                 for &c in group {
                     debug_assert!(max >= i);
                     new_order[c] = max - i;
@@ -215,6 +474,22 @@ impl From<ChainDense> for TiedDense {
     }
 }
 
+impl From<BinaryDense> for TiedDense {
+    /// Convert each order to a tied order with two groups, where the approved
+    /// elements are tied above the rejected elements, which are also tied.
+    fn from(value: BinaryDense) -> Self {
+        let elements = value.elements;
+        let mut out = TiedDense::with_capacity(elements, value.len());
+        for order in value.iter() {
+            out.orders.extend(order.approved());
+            out.orders.extend(order.rejected());
+            let approved = order.approval_count();
+            out.ties.extend((1..elements).map(|j| j != approved));
+        }
+        out
+    }
+}
+
 impl<'a> FromIterator<TiedRef<'a>> for Option<TiedDense> {
     /// Returns [`None`] if any orders have a different number of
     /// elements, or the iterator is empty.
@@ -240,9 +515,14 @@ impl<'a> FromIterator<TiedRef<'a>> for Option<TiedDense> {
 #[cfg(test)]
 mod tests {
     use quickcheck::{Arbitrary, Gen};
+    use rand::{SeedableRng, rngs::StdRng};
 
     use super::*;
-    use crate::tests::{BoundedArbitrary, std_rng};
+    use crate::{
+        OrderOwned,
+        distribution::Uniform,
+        tests::{BoundedArbitrary, shrink_dense, std_rng},
+    };
 
     /// Returns true if this struct is in a valid state, used for debugging.
     fn valid(td: &TiedDense) -> bool {
@@ -276,6 +556,12 @@ mod tests {
             }
             orders
         }
+
+        fn shrink(&self) -> Box<dyn Iterator<Item = Self>> {
+            shrink_dense(self, self.len(), self.elements, Self::truncate, |o, e| {
+                o.remove_element(e)
+            })
+        }
     }
 
     #[quickcheck]
@@ -283,10 +569,228 @@ mod tests {
         valid(&orders)
     }
 
+    #[quickcheck]
+    fn shrink_valid(orders: TiedDense) -> bool {
+        orders.shrink().all(|o| valid(&o))
+    }
+
+    #[cfg(feature = "rayon")]
+    #[quickcheck]
+    fn generate_par(orders: TiedDense, new_orders: u16, seed: u64) -> bool {
+        if orders.elements == 0 {
+            return true;
+        }
+        let new_orders = new_orders as usize % (3 * PAR_CHUNK);
+        let mut a = orders.clone();
+        let mut b = orders;
+        a.generate_uniform_par(&mut StdRng::seed_from_u64(seed), new_orders);
+        b.generate_uniform_par(&mut StdRng::seed_from_u64(seed), new_orders);
+        valid(&a) && a.orders == b.orders && a.ties == b.ties
+    }
+
+    #[quickcheck]
+    fn add_remove_element(orders: TiedDense) -> bool {
+        let mut added = orders.clone();
+        let new = added.add_element();
+        let last = added
+            .iter()
+            .all(|o| o.order().last() == Some(&new) && o.tied().last().is_none_or(|&t| !t));
+        let ok = valid(&added) && last;
+        added.remove_element(new).unwrap();
+        ok && added.orders == orders.orders && added.ties == orders.ties
+    }
+
+    #[quickcheck]
+    fn from_binary(orders: BinaryDense) -> bool {
+        let tied = TiedDense::from(orders.clone());
+        valid(&tied)
+            && tied.len() == orders.len()
+            && tied.iter().zip(orders.iter()).all(|(t, b)| {
+                let groups = t.iter_groups().count();
+                let winners = t.winners().iter().copied();
+                let correct = if b.approval_count() == 0 {
+                    winners.eq(b.rejected())
+                } else {
+                    winners.eq(b.approved())
+                };
+                correct && groups <= 2
+            })
+    }
+
+    #[quickcheck]
+    fn generate_dist(orders: TiedDense, new_orders: u8, seed: u64) -> bool {
+        let mut generated = orders.clone();
+        generated.generate(&mut StdRng::seed_from_u64(seed), &Uniform, usize::from(new_orders));
+        let added = if orders.elements == 0 { 0 } else { usize::from(new_orders) };
+        valid(&generated) && generated.len() == orders.len() + added
+    }
+
+    #[test]
+    fn generate_custom() {
+        // Always returns the order `0 = 1 = ... = n - 1`.
+        struct AllTied;
+        impl OrderDistribution<Tied> for AllTied {
+            fn sample<R: rand::Rng>(&self, _: &mut R, elements: usize) -> Tied {
+                Tied::new((0..elements).collect(), vec![true; elements - 1])
+            }
+        }
+
+        let mut orders = TiedDense::new(3);
+        orders.generate(&mut StdRng::seed_from_u64(0), &AllTied, 2);
+        assert_eq!(orders.len(), 2);
+        assert!(orders.iter().all(|o| o.order() == [0, 1, 2] && o.tied() == [true, true]));
+    }
+
+    #[quickcheck]
+    fn remove_element(orders: TiedDense, target: usize) -> bool {
+        if orders.elements < 2 {
+            return true;
+        }
+        let target = target % orders.elements;
+        let mut removed = orders.clone();
+        removed.remove_element(target).unwrap();
+        // The group of every element, with `target` removed
+        let groups = |order: TiedRef, skip: Option<usize>| {
+            let mut g = vec![0; order.order().len()];
+            for (i, group) in order.iter_groups_ranked() {
+                for &e in group {
+                    g[e] = i;
+                }
+            }
+            if let Some(s) = skip {
+                g.remove(s);
+            }
+            g
+        };
+        valid(&removed)
+            && removed.len() == orders.len()
+            && removed.iter().zip(orders.iter()).all(|(new, old)| {
+                let new = groups(new, None);
+                let old = groups(old, Some(target));
+                (0..new.len())
+                    .all(|a| (0..new.len()).all(|b| new[a].cmp(&new[b]) == old[a].cmp(&old[b])))
+            })
+    }
+
+    #[test]
+    fn remove_element_middle() {
+        let mut orders = TiedDense::new(4);
+        // 3 = 0 > 2 = 1
+        orders.push(TiedRef::new(&[3, 0, 2, 1], &[true, false, true])).unwrap();
+        orders.remove_element(2).unwrap();
+        assert_eq!(orders.get(0).order(), [2, 0, 1]);
+        assert_eq!(orders.get(0).tied(), [true, false]);
+
+        let mut orders = TiedDense::new(4);
+        // 3 > 0 = 2 = 1
+        orders.push(TiedRef::new(&[3, 0, 2, 1], &[false, true, true])).unwrap();
+        orders.remove_element(2).unwrap();
+        assert_eq!(orders.get(0).order(), [2, 0, 1]);
+        assert_eq!(orders.get(0).tied(), [false, true]);
+    }
+
+    #[quickcheck]
+    fn winner_distribution(orders: TiedDense) -> bool {
+        let dist = orders.winner_distribution();
+        let sum: f64 = dist.iter().sum();
+        dist.len() == orders.elements && (orders.is_empty() || (sum - 1.0).abs() < 1e-9)
+    }
+
+    #[test]
+    fn winner_distribution_example() {
+        let mut orders = TiedDense::new(3);
+        orders.push(TiedRef::new(&[0, 1, 2], &[true, false])).unwrap();
+        orders.push(TiedRef::new(&[2, 1, 0], &[false, false])).unwrap();
+        assert_eq!(orders.winner_distribution(), [0.25, 0.25, 0.5]);
+    }
+
+    #[quickcheck]
+    fn swap_remove(mut orders: TiedDense, i: usize) -> bool {
+        if orders.is_empty() {
+            return orders.swap_remove(i).is_none();
+        }
+        let i = i % orders.len();
+        let before = orders.clone();
+        let removed = orders.swap_remove(i).unwrap();
+        valid(&orders)
+            && orders.len() + 1 == before.len()
+            && removed.order() == before.get(i).order()
+            && removed.tied() == before.get(i).tied()
+    }
+
+    #[quickcheck]
+    fn append_push(mut a: TiedDense, new_orders: u8) -> bool {
+        if a.append(&mut TiedDense::new(a.elements + 1)).is_ok() {
+            return false;
+        }
+        let mut b = TiedDense::new(a.elements);
+        if a.elements != 0 {
+            b.generate_uniform(&mut StdRng::seed_from_u64(0), new_orders as usize);
+        }
+        let mut pushed = a.clone();
+        for order in b.iter() {
+            pushed.push(order).unwrap();
+        }
+        a.append(&mut b).unwrap();
+        valid(&a) && a.orders == pushed.orders && a.ties == pushed.ties && b.is_empty()
+    }
+
     #[test]
     fn collect_empty() {
         let v: Vec<TiedRef> = Vec::new();
         let res: Option<TiedDense> = v.into_iter().collect();
         assert!(res.is_none());
     }
+
+    #[quickcheck]
+    fn generate_uniform_with_winner(elements: usize, top: usize, seed: u64) -> bool {
+        let elements = elements % 50 + 1;
+        let top = top % elements;
+        let mut orders = TiedDense::new(elements);
+        orders.generate_uniform_with_winner(&mut StdRng::seed_from_u64(seed), 20, top);
+        valid(&orders) && orders.len() == 20 && orders.iter().all(|o| o.winners() == [top])
+    }
+
+    #[test]
+    fn push_capacity_linear() {
+        let elements = 50;
+        let order = Tied::new_tied(elements);
+        let mut orders = TiedDense::new(elements);
+        for pushes in 1..=10 {
+            orders.push(order.as_ref()).unwrap();
+            assert!(orders.orders.capacity() <= 2 * pushes * elements);
+            assert!(orders.ties.capacity() <= 2 * pushes * (elements - 1));
+        }
+    }
+
+    #[quickcheck]
+    fn break_ties_strict(orders: ChainDense, seed: u64) -> bool {
+        if orders.elements() == 0 {
+            return true;
+        }
+        let tied = TiedDense::from(orders.clone());
+        let random =
+            TiedDense::from(orders.clone()).break_ties_using(&mut StdRng::seed_from_u64(seed));
+        tied.break_ties_by_index() == orders && random == orders
+    }
+
+    #[quickcheck]
+    fn break_ties_keeps_groups(orders: TiedDense, seed: u64) -> bool {
+        let rng = &mut StdRng::seed_from_u64(seed);
+        let strict = [orders.clone().break_ties_by_index(), orders.clone().break_ties_using(rng)];
+        strict.iter().all(|strict| {
+            strict.len() == orders.len()
+                && orders.iter().zip(strict.iter()).all(|(a, b)| {
+                    let mut start = 0;
+                    a.iter_groups().all(|group| {
+                        let mut expected = group.to_vec();
+                        let mut actual = b.order[start..(start + group.len())].to_vec();
+                        start += group.len();
+                        expected.sort_unstable();
+                        actual.sort_unstable();
+                        expected == actual
+                    })
+                })
+        })
+    }
 }
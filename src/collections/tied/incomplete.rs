@@ -10,6 +10,21 @@ use crate::{
     tied::{Tied, TiedI, TiedIRef},
 };
 
+/// How [`TiedIDense::borda`] scores the elements an order does not rank.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum UnrankedPolicy {
+    /// Unranked elements get no points.
+    Zero,
+
+    /// Unranked elements are tied below every ranked element, sharing the
+    /// points of the remaining positions equally.
+    AveragedBottom,
+
+    /// Unranked elements each get the points of the first position after the
+    /// ranked elements, as if they all shared that place.
+    SharedLast,
+}
+
 /// Packed list of [`TiedI`](crate::tied::TiedI)
 #[derive(Debug, PartialEq, Eq)]
 pub struct TiedIDense {
@@ -48,6 +63,52 @@ impl TiedIDense {
         TiedIDense { orders: Vec::new(), ties: Vec::new(), order_end: Vec::new(), elements }
     }
 
+    /// Create an empty collection which can hold `orders` orders without
+    /// reallocating, even if every order ranks all elements.
+    pub fn with_capacity(elements: usize, orders: usize) -> Self {
+        TiedIDense {
+            orders: Vec::with_capacity(orders * elements),
+            ties: Vec::with_capacity(orders * elements.saturating_sub(1)),
+            order_end: Vec::with_capacity(orders),
+            elements,
+        }
+    }
+
+    /// Shrink the capacity of the collection as much as possible.
+    pub fn shrink_to_fit(&mut self) {
+        self.orders.shrink_to_fit();
+        self.ties.shrink_to_fit();
+        self.order_end.shrink_to_fit();
+    }
+
+    /// Remove every order, keeping the number of elements and the allocated
+    /// capacity.
+    pub fn clear(&mut self) {
+        self.orders.clear();
+        self.ties.clear();
+        self.order_end.clear();
+    }
+
+    /// Keep only the first `len` orders, similar to [`Vec::truncate`]. Has no
+    /// effect if `len` is greater than or equal to the number of orders.
+    pub fn truncate(&mut self, len: usize) {
+        if len < self.len() {
+            // Every order has one less tie than it has elements
+            let end = if len == 0 { 0 } else { self.order_end[len - 1] };
+            self.orders.truncate(end);
+            self.ties.truncate(end - len);
+            self.order_end.truncate(len);
+        }
+    }
+
+    /// Estimate of the number of bytes allocated on the heap by the
+    /// collection, based on the capacity of its buffers. Does not include the
+    /// size of the collection itself.
+    pub fn memory_usage(&self) -> usize {
+        (self.orders.capacity() + self.order_end.capacity()) * size_of::<usize>()
+            + self.ties.capacity() * size_of::<bool>()
+    }
+
     pub fn from_parts(
         orders: Vec<usize>,
         ties: Vec<bool>,
@@ -145,6 +206,59 @@ impl TiedIDense {
         firsts
     }
 
+    /// Borda score of every element, summed over all orders. With `n`
+    /// elements, the element at position `p` (starting from 0 at the top)
+    /// gets `n - 1 - p` points. Tied elements share the points of the
+    /// positions they occupy equally, and `unranked` decides the points of
+    /// elements an order does not rank.
+    ///
+    /// ```
+    /// use orders::{
+    ///     OrderOwned,
+    ///     collections::{DenseOrders, TiedIDense, UnrankedPolicy},
+    ///     tied::TiedI,
+    /// };
+    ///
+    /// let mut orders = TiedIDense::new(4);
+    /// orders.push(TiedI::from_slices(4, &[&[2], &[0, 1]]).as_ref()).unwrap();
+    /// assert_eq!(orders.borda(UnrankedPolicy::Zero), vec![1.5, 1.5, 3.0, 0.0]);
+    /// ```
+    pub fn borda(&self, unranked: UnrankedPolicy) -> Vec<f64> {
+        let n = self.elements;
+        let mut score = vec![0.0; n];
+        for order in self.iter() {
+            let mut position = 0;
+            for group in order.iter_groups() {
+                // Sum of points for positions `position..position + len`
+                // divided by `len`.
+                let points = (n - 1 - position) as f64 - (group.len() - 1) as f64 / 2.0;
+                for &c in group {
+                    score[c] += points;
+                }
+                position += group.len();
+            }
+            let missing = n - position;
+            if missing == 0 {
+                continue;
+            }
+            let points = match unranked {
+                UnrankedPolicy::Zero => 0.0,
+                UnrankedPolicy::AveragedBottom => (missing - 1) as f64 / 2.0,
+                UnrankedPolicy::SharedLast => (missing - 1) as f64,
+            };
+            if points != 0.0 {
+                let mut is_ranked = vec![false; n];
+                for &c in order.order() {
+                    is_ranked[c] = true;
+                }
+                for (s, _) in score.iter_mut().zip(is_ranked).filter(|(_, r)| !r) {
+                    *s += points;
+                }
+            }
+        }
+        score
+    }
+
     /// Check if a set of elements is a set of clones such that there does not
     /// exists a element outside the set with ranking i, and two elements in
     /// the set with ranking n and m, where n <= i <= m.
@@ -357,8 +471,8 @@ mod tests {
 
     use super::*;
     use crate::{
-        OrderRef,
-        tests::{BoundedArbitrary, std_rng},
+        OrderOwned, OrderRef,
+        tests::{BoundedArbitrary, shrink_dense, std_rng},
     };
 
     /// Returns true if this struct is in a valid state, used for debugging.
@@ -398,6 +512,12 @@ mod tests {
             }
             orders
         }
+
+        fn shrink(&self) -> Box<dyn Iterator<Item = Self>> {
+            shrink_dense(self, self.len(), self.elements, Self::truncate, |o, e| {
+                o.remove_element(e)
+            })
+        }
     }
 
     #[quickcheck]
@@ -405,6 +525,37 @@ mod tests {
         valid(&orders)
     }
 
+    #[quickcheck]
+    fn shrink_valid(orders: TiedIDense) -> bool {
+        orders.shrink().all(|o| valid(&o))
+    }
+
+    #[quickcheck]
+    fn with_capacity_push(orders: TiedIDense) -> bool {
+        let mut a = TiedIDense::with_capacity(orders.elements, orders.len());
+        let capacity = (a.orders.capacity(), a.ties.capacity(), a.order_end.capacity());
+        for order in orders.iter() {
+            a.push(order).unwrap();
+        }
+        let mut b = orders.clone();
+        b.shrink_to_fit();
+        a == orders
+            && b == orders
+            && capacity == (a.orders.capacity(), a.ties.capacity(), a.order_end.capacity())
+    }
+
+    #[quickcheck]
+    fn truncate_clear(orders: TiedIDense, len: usize) -> bool {
+        let len = len % (orders.len() + 2);
+        let mut truncated = orders.clone();
+        truncated.truncate(len);
+        let prefix = valid(&truncated)
+            && truncated.len() == len.min(orders.len())
+            && truncated.iter().zip(orders.iter()).all(|(a, b)| a == b);
+        truncated.clear();
+        prefix && truncated.is_empty() && truncated.elements() == orders.elements()
+    }
+
     #[quickcheck]
     fn remove(orders: TiedIDense, n: usize) -> bool {
         let old_elements = orders.elements();
@@ -426,6 +577,24 @@ mod tests {
         a == b
     }
 
+    #[quickcheck]
+    fn borda_total(orders: TiedIDense) -> bool {
+        // Averaged bottom hands out every position's points exactly once
+        let n = orders.elements();
+        let total: f64 = orders.borda(UnrankedPolicy::AveragedBottom).iter().sum();
+        let expected = (orders.len() * n * n.saturating_sub(1) / 2) as f64;
+        (total - expected).abs() < 1e-6
+    }
+
+    #[test]
+    fn borda_policies() {
+        let mut orders = TiedIDense::new(5);
+        orders.push(TiedI::from_slices(5, &[&[2], &[0, 1]]).as_ref()).unwrap();
+        assert_eq!(orders.borda(UnrankedPolicy::Zero), vec![2.5, 2.5, 4.0, 0.0, 0.0]);
+        assert_eq!(orders.borda(UnrankedPolicy::AveragedBottom), vec![2.5, 2.5, 4.0, 0.5, 0.5]);
+        assert_eq!(orders.borda(UnrankedPolicy::SharedLast), vec![2.5, 2.5, 4.0, 1.0, 1.0]);
+    }
+
     // These three benches compare different ways to do "generate_uniform".
     #[bench]
     fn bench_add_random(b: &mut Bencher) {
@@ -473,4 +642,14 @@ mod tests {
             }
         });
     }
+
+    #[quickcheck]
+    fn to_owned_vec_round_trip(orders: TiedIDense) -> bool {
+        let owned = orders.to_owned_vec();
+        let mut copy = TiedIDense::new(orders.elements());
+        for order in &owned {
+            copy.push(order.as_ref()).unwrap();
+        }
+        owned.len() == orders.len() && copy == orders
+    }
 }
@@ -2,4 +2,4 @@ mod complete;
 mod incomplete;
 
 pub use complete::TiedDense;
-pub use incomplete::TiedIDense;
+pub use incomplete::{TiedIDense, UnrankedPolicy};
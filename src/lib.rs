@@ -34,6 +34,8 @@ extern crate quickcheck_macros;
 pub mod collections;
 mod orders;
 pub mod partial_order;
+#[cfg(any(test, feature = "quickcheck"))]
+pub mod testing;
 
 pub use orders::*;
 
@@ -48,36 +50,6 @@ fn pairwise_lt(v: &[usize]) -> bool {
     true
 }
 
-fn get_order<T: Ord>(v: &[T], reverse: bool) -> Vec<usize> {
-    if v.is_empty() {
-        return Vec::new();
-    } else if v.len() == 1 {
-        return vec![0];
-    }
-
-    let mut tmp: Vec<(usize, &T)> = Vec::with_capacity(v.len());
-    for (i, el) in v.iter().enumerate() {
-        tmp.push((i, el));
-    }
-    tmp.sort_by(|a, b| (*a.1).cmp(b.1));
-    if reverse {
-        tmp.reverse();
-    }
-    let mut out = vec![0; v.len()];
-    if let Some((b, bs)) = tmp.split_first_mut() {
-        let mut current: &T = b.1;
-        let mut i: usize = 0;
-        for x in bs.iter_mut() {
-            if *x.1 != *current {
-                current = x.1;
-                i += 1;
-            }
-            out[x.0] = i;
-        }
-    }
-    out
-}
-
 // Sort two arrays, sorted according to the values in `b`.
 // Uses insertion sort
 pub(crate) fn sort_using<A, B>(a: &mut [A], b: &mut [B])
@@ -97,20 +69,39 @@ where
     }
 }
 
+use std::mem;
+
 use rand::{
     Rng,
     distr::{Distribution, StandardUniform},
 };
 
+/// Orders at most this long are checked for duplicates by comparing every
+/// pair, which is faster than allocating.
+const UNIQUE_QUADRATIC_MAX: usize = 32;
+
+/// Returns true if every element of `order` is less than `elements` and no
+/// element occurs more than once.
 fn unique_and_bounded(elements: usize, order: &[usize]) -> bool {
+    if order.iter().any(|&a| a >= elements) {
+        return false;
+    }
+    if order.len() <= UNIQUE_QUADRATIC_MAX {
+        unique_quadratic(order)
+    } else if elements / 8 <= order.len() {
+        let mut seen = vec![false; elements];
+        order.iter().all(|&a| !mem::replace(&mut seen[a], true))
+    } else {
+        // Avoid allocating a huge table for a short order of many elements
+        let mut sorted = order.to_vec();
+        sorted.sort_unstable();
+        sorted.windows(2).all(|w| w[0] != w[1])
+    }
+}
+
+fn unique_quadratic(order: &[usize]) -> bool {
     for (i, &a) in order.iter().enumerate() {
-        if a >= elements {
-            return false;
-        }
-        for (j, &b) in order.iter().enumerate() {
-            if i == j {
-                continue;
-            }
+        for &b in &order[(i + 1)..] {
             if a == b {
                 return false;
             }
@@ -127,59 +118,31 @@ pub(crate) fn add_bool<R: Rng>(rng: &mut R, v: &mut Vec<bool>, n: usize) {
 mod tests {
     use std::mem;
 
-    use quickcheck::{Arbitrary, Gen};
-    use rand::{SeedableRng, rngs::StdRng};
-
     use super::*;
-
-    // `Gen` contains a rng, but it's a private member so this method is used to get
-    // a standard rng generated from `Gen`
-    pub fn std_rng(g: &mut Gen) -> StdRng {
-        let mut seed = [0u8; 32];
-        for i in 0..32 {
-            seed[i] = Arbitrary::arbitrary(g);
-        }
-        StdRng::from_seed(seed)
-    }
-
-    /// Like [`Arbitrary`], but generates numerical values less than `g.size()`.
-    pub trait BoundedArbitrary: Clone + 'static {
-        fn arbitrary(g: &mut Gen) -> Self;
-    }
-
-    impl BoundedArbitrary for usize {
-        fn arbitrary(g: &mut Gen) -> Self {
-            let n: Self = Arbitrary::arbitrary(g);
-            n % g.size()
-        }
-    }
-
-    // Generate elementwise for tuples
-    impl<T: BoundedArbitrary> BoundedArbitrary for (T, T) {
-        fn arbitrary(g: &mut Gen) -> Self {
-            (BoundedArbitrary::arbitrary(g), BoundedArbitrary::arbitrary(g))
-        }
-    }
-
-    impl<T: BoundedArbitrary> BoundedArbitrary for (T, T, T) {
-        fn arbitrary(g: &mut Gen) -> Self {
-            (
-                BoundedArbitrary::arbitrary(g),
-                BoundedArbitrary::arbitrary(g),
-                BoundedArbitrary::arbitrary(g),
-            )
-        }
-    }
-
-    impl<T: BoundedArbitrary> BoundedArbitrary for (T, T, T, T) {
-        fn arbitrary(g: &mut Gen) -> Self {
-            (
-                BoundedArbitrary::arbitrary(g),
-                BoundedArbitrary::arbitrary(g),
-                BoundedArbitrary::arbitrary(g),
-                BoundedArbitrary::arbitrary(g),
-            )
-        }
+    pub use crate::testing::{BoundedArbitrary, std_rng};
+
+    /// Shrink a dense collection of `len` orders and `elements` elements, by
+    /// keeping fewer orders or by removing its last element using
+    /// `remove_element`. Never removes the last remaining element.
+    pub fn shrink_dense<T: Clone + 'static>(
+        orders: &T,
+        len: usize,
+        elements: usize,
+        truncate: fn(&mut T, usize),
+        remove_element: fn(&mut T, usize) -> Result<(), &'static str>,
+    ) -> Box<dyn Iterator<Item = T>> {
+        let mut lens = vec![0, len / 2, len.saturating_sub(1)];
+        lens.dedup();
+        let original = orders.clone();
+        let fewer_orders = lens.into_iter().filter(move |&l| l < len).map(move |l| {
+            let mut smaller = original.clone();
+            truncate(&mut smaller, l);
+            smaller
+        });
+        let mut smaller = orders.clone();
+        let fewer_elements =
+            (elements > 1 && remove_element(&mut smaller, elements - 1).is_ok()).then_some(smaller);
+        Box::new(fewer_orders.chain(fewer_elements))
     }
 
     #[quickcheck]
@@ -194,6 +157,30 @@ mod tests {
         bbb.is_sorted()
     }
 
+    fn unique_and_bounded_reference(elements: usize, order: &[usize]) -> bool {
+        order.iter().all(|&a| a < elements) && unique_quadratic(order)
+    }
+
+    #[quickcheck]
+    fn unique_and_bounded_same(elements: u8, order: Vec<u8>, big: bool) -> bool {
+        // Also test elements much larger than the order
+        let elements = if big { usize::from(elements) << 12 } else { usize::from(elements) };
+        let order: Vec<usize> = order.into_iter().map(usize::from).collect();
+        let mut permutation: Vec<usize> = (0..order.len()).rev().collect();
+        let permutation_ok = unique_and_bounded(order.len(), &permutation);
+        // Repeat the last element
+        permutation.extend(permutation.last().copied());
+        unique_and_bounded(elements, &order) == unique_and_bounded_reference(elements, &order)
+            && permutation_ok
+            && (order.is_empty() || !unique_and_bounded(permutation.len(), &permutation))
+    }
+
+    #[bench]
+    fn bench_unique_and_bounded(b: &mut test::Bencher) {
+        let order: Vec<usize> = (0..100_000).rev().collect();
+        b.iter(|| unique_and_bounded(order.len(), &order));
+    }
+
     #[test]
     fn sort_using_empty() {
         sort_using::<usize, usize>(&mut [], &mut []);
@@ -1,3 +1,5 @@
+use std::fmt;
+
 use rand::{Rng, distr::StandardUniform};
 
 pub use super::BinaryRef;
@@ -8,7 +10,7 @@ use crate::{
 };
 
 /// An order with two groups
-#[derive(Debug)]
+#[derive(Debug, PartialEq, Eq, Hash)]
 pub struct Binary {
     pub(super) values: Vec<bool>,
 }
@@ -73,6 +75,12 @@ impl Order for Binary {
     }
 }
 
+impl fmt::Display for Binary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.as_ref().fmt(f)
+    }
+}
+
 impl<'a> OrderOwned<'a> for Binary {
     type Ref = BinaryRef<'a>;
 
@@ -116,6 +124,16 @@ mod tests {
         }
     }
 
+    #[quickcheck]
+    fn approved_rejected(b: Binary) -> bool {
+        let b = b.as_ref();
+        let mut all: Vec<usize> = b.approved().chain(b.rejected()).collect();
+        all.sort_unstable();
+        b.approved().count() == b.approval_count()
+            && b.approved().all(|i| b.values()[i])
+            && all == (0..b.len()).collect::<Vec<usize>>()
+    }
+
     #[quickcheck]
     fn as_partial(b: Binary) -> bool {
         let po = b.to_partial();
@@ -148,4 +166,11 @@ mod tests {
     fn complete(b: Binary) -> bool {
         b.len() == b.elements()
     }
+
+    #[test]
+    fn display() {
+        let b = Binary::new(vec![true, false, true, false]);
+        assert_eq!(b.to_string(), "{0, 2} > {1, 3}");
+        assert_eq!(Binary::new(vec![false]).to_string(), "{} > {0}");
+    }
 }
@@ -1,3 +1,5 @@
+use std::fmt;
+
 use super::Binary;
 use crate::OrderRef;
 
@@ -11,6 +13,12 @@ impl<'a> BinaryRef<'a> {
         BinaryRef { values: v }
     }
 
+    /// Number of elements, whether they are approved or not.
+    pub fn elements(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Same as [`BinaryRef::elements`], as there is a value for every element.
     pub fn len(&self) -> usize {
         self.values.len()
     }
@@ -22,6 +30,53 @@ impl<'a> BinaryRef<'a> {
     pub fn values(&self) -> &'a [bool] {
         self.values
     }
+
+    /// Iterator over the approved elements, i.e. the elements with value
+    /// `true`, in increasing order.
+    ///
+    /// ```
+    /// use orders::binary::BinaryRef;
+    ///
+    /// let order = BinaryRef::new(&[true, false, false, true]);
+    /// assert!(order.approved().eq([0, 3]));
+    /// assert!(order.rejected().eq([1, 2]));
+    /// ```
+    pub fn approved(&self) -> impl Iterator<Item = usize> + 'a {
+        self.values.iter().enumerate().filter(|(_, v)| **v).map(|(i, _)| i)
+    }
+
+    /// Iterator over the rejected elements, i.e. the elements with value
+    /// `false`, in increasing order.
+    pub fn rejected(&self) -> impl Iterator<Item = usize> + 'a {
+        self.values.iter().enumerate().filter(|(_, v)| !**v).map(|(i, _)| i)
+    }
+
+    /// Number of approved elements.
+    pub fn approval_count(&self) -> usize {
+        self.values.iter().filter(|v| **v).count()
+    }
+}
+
+impl fmt::Display for BinaryRef<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut approved = self.approved().peekable();
+        let mut rejected = self.rejected().peekable();
+        f.write_str("{")?;
+        while let Some(i) = approved.next() {
+            write!(f, "{i}")?;
+            if approved.peek().is_some() {
+                f.write_str(", ")?;
+            }
+        }
+        f.write_str("} > {")?;
+        while let Some(i) = rejected.next() {
+            write!(f, "{i}")?;
+            if rejected.peek().is_some() {
+                f.write_str(", ")?;
+            }
+        }
+        f.write_str("}")
+    }
 }
 
 impl OrderRef for BinaryRef<'_> {
@@ -0,0 +1,93 @@
+//! Helpers for the compact byte format used by `to_bytes` and `from_bytes`.
+//!
+//! Integers are written as unsigned LEB128 varints: 7 bits per byte, least
+//! significant group first, with the high bit set on every byte except the
+//! last. Only the shortest encoding of a value is accepted when reading.
+
+/// Append `x` to `out` as a varint.
+pub(crate) fn write_varint(out: &mut Vec<u8>, mut x: usize) {
+    while x >= 0x80 {
+        out.push((x as u8 & 0x7f) | 0x80);
+        x >>= 7;
+    }
+    out.push(x as u8);
+}
+
+/// Read a varint from the start of `bytes`, advancing it past the varint.
+///
+/// Returns `None` if `bytes` ends early, the value doesn't fit in a `usize`
+/// or the encoding isn't the shortest one.
+pub(crate) fn read_varint(bytes: &mut &[u8]) -> Option<usize> {
+    let mut x: usize = 0;
+    let mut shift = 0;
+    loop {
+        let (&b, rest) = bytes.split_first()?;
+        *bytes = rest;
+        let group = usize::from(b & 0x7f);
+        if shift >= usize::BITS || (group << shift) >> shift != group {
+            return None;
+        }
+        x |= group << shift;
+        shift += 7;
+        if b & 0x80 == 0 {
+            // A trailing zero group means the encoding is longer than needed
+            return if b == 0 && shift > 7 { None } else { Some(x) };
+        }
+    }
+}
+
+/// Read `elements` varints from the start of `bytes`, advancing it past them.
+pub(crate) fn read_varints(bytes: &mut &[u8], elements: usize) -> Option<Vec<usize>> {
+    // Every varint takes at least one byte
+    if bytes.len() < elements {
+        return None;
+    }
+    let mut out = Vec::with_capacity(elements);
+    for _ in 0..elements {
+        out.push(read_varint(bytes)?);
+    }
+    Some(out)
+}
+
+/// Append `bits` to `out`, packed 8 per byte with the first bit in the least
+/// significant bit. Unused bits of the last byte are zero.
+pub(crate) fn write_bits(out: &mut Vec<u8>, bits: &[bool]) {
+    for chunk in bits.chunks(8) {
+        out.push(chunk.iter().rev().fold(0, |byte, &b| (byte << 1) | u8::from(b)));
+    }
+}
+
+/// Read `n` bits written by [`write_bits`] from the whole of `bytes`.
+///
+/// Returns `None` if `bytes` has the wrong length or an unused bit is set.
+pub(crate) fn read_bits(bytes: &[u8], n: usize) -> Option<Vec<bool>> {
+    if bytes.len() != n.div_ceil(8) {
+        return None;
+    }
+    let bits: Vec<bool> = (0..bytes.len() * 8).map(|i| bytes[i / 8] >> (i % 8) & 1 == 1).collect();
+    if bits[n..].contains(&true) {
+        return None;
+    }
+    Some(bits[..n].to_vec())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[quickcheck]
+    fn varint_round_trip(x: usize) -> bool {
+        let mut out = Vec::new();
+        write_varint(&mut out, x);
+        let mut bytes = &out[..];
+        read_varint(&mut bytes) == Some(x) && bytes.is_empty()
+    }
+
+    #[test]
+    fn varint_rejects() {
+        assert_eq!(read_varint(&mut &[0x80, 0x00][..]), None);
+        assert_eq!(read_varint(&mut &[0x80][..]), None);
+        assert_eq!(read_varint(&mut &[0xff; 11][..]), None);
+        assert_eq!(read_varint(&mut &[0xac, 0x02][..]), Some(300));
+    }
+}
@@ -1,3 +1,5 @@
+use std::fmt;
+
 use rand::{
     Rng,
     distr::{Distribution, Uniform},
@@ -9,7 +11,7 @@ use crate::{
     partial_order::{PartialOrder, PartialOrderManual},
 };
 
-#[derive(Debug)]
+#[derive(Debug, PartialEq, Eq, Hash)]
 pub struct Cardinal {
     pub(super) values: Vec<usize>,
 }
@@ -38,6 +40,47 @@ impl Cardinal {
         self.values.clone_from_slice(source.values);
     }
 
+    /// Clip every value to be in the range `min..=max`. A `Cardinal` doesn't
+    /// store its range, so it has to be given here.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `min > max`.
+    ///
+    /// ```
+    /// use orders::cardinal::Cardinal;
+    ///
+    /// let mut c = Cardinal::new(vec![0, 5, 9]);
+    /// c.clamp(1, 6);
+    /// assert_eq!(c, Cardinal::new(vec![1, 5, 6]));
+    /// ```
+    pub fn clamp(&mut self, min: usize, max: usize) {
+        for v in &mut self.values {
+            *v = (*v).clamp(min, max);
+        }
+    }
+
+    /// Add the values of `other` to the values of `self`, element by element,
+    /// but never above `max`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the orders have a different number of elements.
+    ///
+    /// ```
+    /// use orders::cardinal::{Cardinal, CardinalRef};
+    ///
+    /// let mut c = Cardinal::new(vec![0, 5, 9]);
+    /// c.saturating_add(CardinalRef::new(&[3, 3, 3]), 10);
+    /// assert_eq!(c, Cardinal::new(vec![3, 8, 10]));
+    /// ```
+    pub fn saturating_add(&mut self, other: CardinalRef, max: usize) {
+        assert!(self.values.len() == other.values.len());
+        for (v, &o) in self.values.iter_mut().zip(other.values) {
+            *v = v.saturating_add(o).min(max);
+        }
+    }
+
     pub fn random<R: Rng>(rng: &mut R, elements: usize, min: usize, max: usize) -> Cardinal {
         assert!(min <= max);
         let dist = Uniform::new_inclusive(min, max).unwrap();
@@ -69,7 +112,14 @@ impl Order for Cardinal {
                 tmp.set_ord(i, j, e1.cmp(e2));
             }
         }
-        tmp.finish()
+        // SAFETY: Comparing values is transitive.
+        unsafe { tmp.finish_unchecked() }
+    }
+}
+
+impl fmt::Display for Cardinal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.as_ref().fmt(f)
     }
 }
 
@@ -91,6 +141,7 @@ mod tests {
     use crate::{
         partial_order::tests::valid,
         tests::{BoundedArbitrary, std_rng},
+        tied::Tied,
     };
 
     impl Arbitrary for Cardinal {
@@ -110,6 +161,36 @@ mod tests {
         }
     }
 
+    #[quickcheck]
+    fn to_tied_i_complete(b: Cardinal) -> bool {
+        let min = b.values.iter().copied().min().unwrap_or(0);
+        let tied_i = b.as_ref().to_tied_i(min);
+        let tied = Tied::from(b.as_ref());
+        tied_i.order() == tied.order() && tied_i.tied() == tied.tied()
+    }
+
+    #[test]
+    fn to_tied_i_cutoff() {
+        let order = CardinalRef::new(&[2, 0, 4, 1, 2]).to_tied_i(2);
+        assert_eq!(order.as_ref().elements(), 5);
+        assert_eq!(order.order(), [2, 0, 4]);
+        assert_eq!(order.tied(), [false, true]);
+    }
+
+    #[quickcheck]
+    fn winners_max(b: Cardinal) -> bool {
+        let winners = b.as_ref().winners();
+        let max = b.values.iter().max();
+        (0..b.len()).all(|i| winners.contains(&i) == (Some(&b.values[i]) == max))
+    }
+
+    #[test]
+    fn winners() {
+        assert_eq!(CardinalRef::new(&[1, 4, 2]).winners(), [1]);
+        assert_eq!(CardinalRef::new(&[2, 2, 2]).winners(), [0, 1, 2]);
+        assert!(CardinalRef::new(&[]).winners().is_empty());
+    }
+
     #[quickcheck]
     fn as_partial(b: Cardinal) -> bool {
         let po = b.to_partial();
@@ -142,4 +223,30 @@ mod tests {
     fn complete(b: Cardinal) -> bool {
         b.len() == b.elements()
     }
+
+    #[test]
+    fn display() {
+        let c = Cardinal::new(vec![3, 0, 5]);
+        assert_eq!(c.to_string(), "0:3, 1:0, 2:5");
+    }
+
+    #[quickcheck]
+    fn clamp_valid_noop(b: Cardinal) -> bool {
+        let (Some(&min), Some(&max)) = (b.values.iter().min(), b.values.iter().max()) else {
+            return true;
+        };
+        let mut clamped = b.clone();
+        clamped.clamp(min, max);
+        clamped == b
+    }
+
+    #[quickcheck]
+    fn saturating_add_max(a: Cardinal, max: usize) -> bool {
+        let mut sum = a.clone();
+        sum.saturating_add(a.as_ref(), max);
+        sum.values
+            .iter()
+            .zip(&a.values)
+            .all(|(&s, &v)| s <= max && s == v.saturating_mul(2).min(max))
+    }
 }
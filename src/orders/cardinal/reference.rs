@@ -1,4 +1,6 @@
-use crate::{OrderRef, binary::Binary, cardinal::Cardinal};
+use std::fmt;
+
+use crate::{OrderRef, binary::Binary, cardinal::Cardinal, tied::TiedI};
 
 pub struct CardinalRef<'a> {
     pub(crate) values: &'a [usize],
@@ -9,6 +11,12 @@ impl<'a> CardinalRef<'a> {
         CardinalRef { values: s }
     }
 
+    /// Number of elements, each given a value by the order. Same as
+    /// [`CardinalRef::len`].
+    pub fn elements(&self) -> usize {
+        self.values.len()
+    }
+
     /// Returns the number of elements in the order
     pub fn len(&self) -> usize {
         self.values.len()
@@ -22,12 +30,59 @@ impl<'a> CardinalRef<'a> {
         self.values
     }
 
+    /// Returns every element with the maximum value, in increasing order.
+    /// Returns an empty vector if there are no elements.
+    ///
+    /// ```
+    /// use orders::cardinal::CardinalRef;
+    ///
+    /// assert_eq!(CardinalRef::new(&[3, 5, 1, 5]).winners(), [1, 3]);
+    /// ```
+    pub fn winners(&self) -> Vec<usize> {
+        let Some(&max) = self.values.iter().max() else {
+            return Vec::new();
+        };
+        (0..self.len()).filter(|&i| self.values[i] == max).collect()
+    }
+
     /// Convert to binary order, where any value less than `cutoff` becomes
     /// `false` and larger becomes `true`.
     pub fn to_binary(&self, cutoff: usize) -> Binary {
         let values = self.values.iter().map(|x| *x >= cutoff).collect();
         Binary::new(values)
     }
+
+    /// Convert to an incomplete tied order of the elements with a value of at
+    /// least `cutoff`, where higher values are ranked higher and equal values
+    /// are tied. Elements with a value less than `cutoff` are unranked.
+    ///
+    /// ```
+    /// use orders::cardinal::CardinalRef;
+    ///
+    /// let order = CardinalRef::new(&[3, 1, 5, 3]).to_tied_i(2);
+    /// assert_eq!(order.order(), [2, 0, 3]);
+    /// assert_eq!(order.tied(), [false, true]);
+    /// ```
+    pub fn to_tied_i(&self, cutoff: usize) -> TiedI {
+        let mut list: Vec<(usize, usize)> =
+            self.values.iter().copied().enumerate().filter(|&(_, v)| v >= cutoff).collect();
+        list.sort_by(|(_, a), (_, b)| a.cmp(b).reverse());
+        let tied: Vec<bool> = list.windows(2).map(|w| w[0].1 == w[1].1).collect();
+        let order: Vec<usize> = list.into_iter().map(|(i, _)| i).collect();
+        TiedI::new(self.len(), order, tied)
+    }
+}
+
+impl fmt::Display for CardinalRef<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, v) in self.values.iter().enumerate() {
+            if i != 0 {
+                f.write_str(", ")?;
+            }
+            write!(f, "{i}:{v}")?;
+        }
+        Ok(())
+    }
 }
 
 impl OrderRef for CardinalRef<'_> {
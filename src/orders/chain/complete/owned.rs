@@ -1,10 +1,14 @@
-use std::cmp;
+use std::{cmp, fmt, str::FromStr};
 
 use rand::{Rng, prelude::SliceRandom};
 
 use crate::{
-    Order, OrderOwned,
+    Order, OrderOwned, ParseError,
     chain::{ChainI, ChainRef},
+    orders::{
+        bytes::{read_varints, write_varint},
+        parse::parse_groups,
+    },
     partial_order::PartialOrder,
     unique_and_bounded,
 };
@@ -13,7 +17,11 @@ use crate::{
 ///
 /// Consists of a list of elements, arranged from highest to lowest elements,
 /// ordering all elements.
-#[derive(Debug)]
+///
+/// Implements [`Ord`] by comparing the orders lexicographically. This is only
+/// useful for sorting and searching, the comparison has no meaning in terms of
+/// the orders themselves.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct Chain {
     pub(crate) order: Vec<usize>,
 }
@@ -101,6 +109,57 @@ impl Chain {
         Chain { order }
     }
 
+    /// Generate a random order of `elements`, uniformly among those ranking
+    /// `top` highest.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `top >= elements`.
+    pub fn random_with_top<R: Rng>(rng: &mut R, elements: usize, top: usize) -> Chain {
+        assert!(top < elements);
+        let mut order: Vec<usize> = (0..elements).collect();
+        order.swap(0, top);
+        order[1..].shuffle(rng);
+        Chain { order }
+    }
+
+    /// Encode the order in a compact byte format, read by
+    /// [`Chain::from_bytes`].
+    ///
+    /// The layout is every element of the order, from the highest ranked to
+    /// the lowest, each written as an unsigned
+    /// [LEB128](https://en.wikipedia.org/wiki/LEB128) varint. The number of
+    /// elements is not included.
+    ///
+    /// ```
+    /// use orders::chain::Chain;
+    ///
+    /// let order = Chain::new(vec![2, 0, 1]);
+    /// assert_eq!(order.to_bytes(), [2, 0, 1]);
+    /// assert_eq!(Chain::from_bytes(&order.to_bytes(), 3), Some(order));
+    /// ```
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out = Vec::with_capacity(self.order.len());
+        for &c in &self.order {
+            write_varint(&mut out, c);
+        }
+        out
+    }
+
+    /// Decode an order of `elements` written by [`Chain::to_bytes`].
+    ///
+    /// Returns [`None`] if `bytes` isn't exactly the encoding of a permutation
+    /// of `elements`.
+    pub fn from_bytes(bytes: &[u8], elements: usize) -> Option<Chain> {
+        let mut bytes = bytes;
+        let order = read_varints(&mut bytes, elements)?;
+        if bytes.is_empty() && unique_and_bounded(elements, &order) {
+            Some(Chain { order })
+        } else {
+            None
+        }
+    }
+
     /// Lossless conversion to `Chain`.
     pub fn to_incomplete(self) -> ChainI {
         let Self { order } = self;
@@ -109,6 +168,25 @@ impl Chain {
     }
 }
 
+impl FromStr for Chain {
+    type Err = ParseError;
+
+    /// Parse an order of elements separated by `>`, from highest to lowest.
+    /// The number of elements is the number of elements in the string.
+    ///
+    /// ```
+    /// use orders::chain::Chain;
+    ///
+    /// let order: Chain = "2 > 0 > 1".parse().unwrap();
+    /// assert_eq!(order.into_inner(), [2, 0, 1]);
+    /// assert!("2 > 0".parse::<Chain>().is_err());
+    /// ```
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (order, _) = parse_groups(s, false)?;
+        Ok(Chain { order })
+    }
+}
+
 impl Order for Chain {
     fn elements(&self) -> usize {
         self.order.len()
@@ -123,6 +201,12 @@ impl Order for Chain {
     }
 }
 
+impl fmt::Display for Chain {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.as_ref().fmt(f)
+    }
+}
+
 impl<'a> OrderOwned<'a> for Chain {
     type Ref = ChainRef<'a>;
 
@@ -130,3 +214,89 @@ impl<'a> OrderOwned<'a> for Chain {
         ChainRef { order: &self.order }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use quickcheck::{Arbitrary, Gen};
+    use rand::{SeedableRng, rngs::StdRng};
+
+    use super::*;
+    use crate::tests::{BoundedArbitrary, std_rng};
+
+    impl Arbitrary for Chain {
+        fn arbitrary(g: &mut Gen) -> Self {
+            let elements: usize = BoundedArbitrary::arbitrary(g);
+            Chain::random(&mut std_rng(g), elements)
+        }
+    }
+
+    #[test]
+    fn from_str_errors() {
+        assert_eq!("".parse::<Chain>().unwrap().elements(), 0);
+        assert_eq!("1>0>1".parse::<Chain>().unwrap_err(), ParseError::Repeated(1));
+        assert_eq!("2>0".parse::<Chain>().unwrap_err(), ParseError::Missing(1));
+        assert_eq!(
+            "1>0=2".parse::<Chain>().unwrap_err(),
+            ParseError::InvalidElement("0=2".to_string())
+        );
+        assert_eq!(
+            "1>>0".parse::<Chain>().unwrap_err(),
+            ParseError::InvalidElement("".to_string())
+        );
+    }
+
+    #[quickcheck]
+    fn display_round_trip(order: Chain) -> bool {
+        order.to_string().parse::<Chain>() == Ok(order)
+    }
+
+    #[quickcheck]
+    fn ord_consistent(a: Chain, b: Chain, c: Chain) -> bool {
+        let antisymmetric = a.cmp(&b) == b.cmp(&a).reverse() && (a.cmp(&b).is_eq() == (a == b));
+        let transitive = !(a <= b && b <= c) || a <= c;
+        antisymmetric && transitive
+    }
+
+    #[quickcheck]
+    fn kendall_distance_symmetric(a: Chain, seed: u64) -> bool {
+        let b = Chain::random(&mut StdRng::seed_from_u64(seed), a.elements());
+        let mut reversed = a.clone();
+        reversed.order.reverse();
+        let n = a.elements();
+        a.as_ref().kendall_distance(b.as_ref()) == b.as_ref().kendall_distance(a.as_ref())
+            && a.as_ref().kendall_distance(a.as_ref()) == 0
+            && a.as_ref().kendall_distance(reversed.as_ref()) == n * n.saturating_sub(1) / 2
+    }
+
+    #[quickcheck]
+    fn to_specific_is_winner(order: Chain) -> bool {
+        if order.elements() == 0 {
+            return true;
+        }
+        let specific = order.as_ref().to_specific();
+        specific.value() == order.as_ref().to_incomplete().winner()
+            && specific.elements() == order.elements()
+    }
+
+    #[quickcheck]
+    fn random_with_top_winner(elements: usize, top: usize, seed: u64) -> bool {
+        let elements = elements % 50 + 1;
+        let top = top % elements;
+        let order = Chain::random_with_top(&mut StdRng::seed_from_u64(seed), elements, top);
+        order.elements() == elements && order.as_ref().to_incomplete().winner() == top
+    }
+
+    #[quickcheck]
+    fn bytes_round_trip(order: Chain) -> bool {
+        Chain::from_bytes(&order.to_bytes(), order.elements()) == Some(order)
+    }
+
+    #[test]
+    fn from_bytes_corrupt() {
+        assert_eq!(Chain::from_bytes(&[0, 0], 2), None);
+        assert_eq!(Chain::from_bytes(&[0, 2], 2), None);
+        assert_eq!(Chain::from_bytes(&[0, 1, 2], 2), None);
+        assert_eq!(Chain::from_bytes(&[0], 2), None);
+        assert_eq!(Chain::from_bytes(&[], 0), Some(Chain::new(Vec::new())));
+    }
+}
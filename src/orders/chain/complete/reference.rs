@@ -1,6 +1,10 @@
+use std::fmt;
+
 use crate::{
     OrderRef,
     chain::{Chain, ChainIRef},
+    orders::parse::write_groups,
+    specific::Specific,
     unique_and_bounded,
 };
 
@@ -40,10 +44,70 @@ impl<'a> ChainRef<'a> {
         self.order.len()
     }
 
+    /// Length of the order. A complete chain ranks every element, so this is
+    /// the same as [`ChainRef::elements`].
+    pub fn len(&self) -> usize {
+        self.order.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
     pub fn top(&self, n: usize) -> &[usize] {
         &self.order[..n]
     }
 
+    /// The [Kendall tau distance](https://en.wikipedia.org/wiki/Kendall_tau_distance)
+    /// between `self` and `other`, i.e. the number of pairs of elements which
+    /// the two orders rank differently.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the orders have a different number of elements.
+    ///
+    /// ```
+    /// use orders::chain::ChainRef;
+    ///
+    /// let a = ChainRef::new(&[0, 1, 2]);
+    /// let b = ChainRef::new(&[1, 2, 0]);
+    /// assert_eq!(a.kendall_distance(b), 2);
+    /// ```
+    pub fn kendall_distance(&self, other: ChainRef) -> usize {
+        assert!(self.elements() == other.elements());
+        let mut position = vec![0; self.elements()];
+        for (i, &el) in other.order.iter().enumerate() {
+            position[el] = i;
+        }
+        let mut distance = 0;
+        for (i, &a) in self.order.iter().enumerate() {
+            for &b in &self.order[(i + 1)..] {
+                if position[a] > position[b] {
+                    distance += 1;
+                }
+            }
+        }
+        distance
+    }
+
+    /// Returns the top ranked element as a [`Specific`] over the same
+    /// elements.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the order has no elements.
+    ///
+    /// ```
+    /// use orders::chain::ChainRef;
+    ///
+    /// let order = ChainRef::new(&[2, 0, 1]);
+    /// assert_eq!(order.to_specific().value(), 2);
+    /// ```
+    pub fn to_specific(&self) -> Specific {
+        assert!(!self.is_empty());
+        Specific::new(self.order[0], self.elements())
+    }
+
     pub fn to_incomplete(self) -> ChainIRef<'a> {
         let Self { order } = self;
         let elements = order.len();
@@ -51,6 +115,12 @@ impl<'a> ChainRef<'a> {
     }
 }
 
+impl fmt::Display for ChainRef<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_groups(f, self.order, &[])
+    }
+}
+
 impl OrderRef for ChainRef<'_> {
     type Owned = Chain;
 
@@ -1,3 +1,5 @@
+use std::{cmp::Ordering, fmt};
+
 use rand::{
     Rng,
     seq::{IteratorRandom, SliceRandom},
@@ -11,7 +13,11 @@ use crate::{
 };
 
 /// Incomplete version of [`Chain`]
-#[derive(Debug, PartialEq, Eq)]
+///
+/// Implements [`Ord`] by comparing the orders lexicographically, using the
+/// number of elements as a tiebreaker. This is only useful for sorting and
+/// searching, the comparison has no meaning in terms of the orders themselves.
+#[derive(Debug, PartialEq, Eq, Hash)]
 pub struct ChainI {
     pub(crate) elements: usize,
     pub(crate) order: Vec<usize>,
@@ -28,6 +34,18 @@ impl Clone for ChainI {
     }
 }
 
+impl PartialOrd for ChainI {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for ChainI {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.order.cmp(&other.order).then(self.elements.cmp(&other.elements))
+    }
+}
+
 impl ChainI {
     pub fn new(elements: usize, order: Vec<usize>) -> Self {
         Self::try_new(elements, order).unwrap()
@@ -41,6 +59,32 @@ impl ChainI {
         ChainI { elements, order }
     }
 
+    /// Rank `element` below every element currently in the order.
+    ///
+    /// Returns `Err` if `element` is already ranked or is not less than the
+    /// number of elements.
+    ///
+    /// ```
+    /// use orders::chain::ChainI;
+    ///
+    /// let mut order = ChainI::new(4, Vec::new());
+    /// order.push_rank(2).unwrap();
+    /// order.push_rank(0).unwrap();
+    /// assert!(order.push_rank(2).is_err());
+    /// assert!(order.push_rank(4).is_err());
+    /// assert_eq!(order, ChainI::new(4, vec![2, 0]));
+    /// ```
+    pub fn push_rank(&mut self, element: usize) -> Result<(), &'static str> {
+        if element >= self.elements {
+            return Err("Element not in order");
+        }
+        if self.order.contains(&element) {
+            return Err("Element already ranked");
+        }
+        self.order.push(element);
+        Ok(())
+    }
+
     /// Clones from `source` to `self`, similar to [`Clone::clone_from`].
     pub fn clone_from_ref(&mut self, source: ChainIRef) {
         self.order.clone_from_slice(source.order);
@@ -80,11 +124,7 @@ impl Order for ChainI {
 
     fn to_partial(self) -> PartialOrder {
         let mut manual = PartialOrderManual::new(self.elements());
-        for (i1, e1) in self.order.iter().enumerate() {
-            for e2 in &self.order[(i1 + 1)..] {
-                manual.set(*e2, *e1);
-            }
-        }
+        manual.set_chain(&self.order);
         // SAFETY: We set the relations in `self.order`, including transitive relations.
         // The elements in `rest` have no relations with eachother, or the
         // non-ordered elements.
@@ -92,6 +132,12 @@ impl Order for ChainI {
     }
 }
 
+impl fmt::Display for ChainI {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.as_ref().fmt(f)
+    }
+}
+
 impl<'a> OrderOwned<'a> for ChainI {
     type Ref = ChainIRef<'a>;
 
@@ -103,11 +149,13 @@ impl<'a> OrderOwned<'a> for ChainI {
 #[cfg(test)]
 mod tests {
     use quickcheck::{Arbitrary, Gen};
+    use rand::{SeedableRng, rngs::StdRng};
 
     use super::*;
     use crate::{
         partial_order::tests::valid,
         tests::{BoundedArbitrary, std_rng},
+        unique_and_bounded,
     };
 
     impl Arbitrary for ChainI {
@@ -174,8 +222,60 @@ mod tests {
         valid(&po)
     }
 
+    #[quickcheck]
+    fn complete_keeps_prefix(b: ChainI, seed: u64) -> bool {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let random = b.as_ref().complete_random(&mut rng);
+        let sorted = b.as_ref().complete_sorted();
+        [random, sorted].iter().all(|c| {
+            c.elements() == b.elements
+                && unique_and_bounded(b.elements, &c.order)
+                && c.order.starts_with(&b.order)
+        })
+    }
+
+    #[quickcheck]
+    fn ref_len(b: ChainI) -> bool {
+        let r = b.as_ref();
+        r.len() == b.len() && r.elements() == b.elements && r.is_empty() == b.order.is_empty()
+    }
+
     #[quickcheck]
     fn len(b: ChainI) -> bool {
         b.len() <= b.elements()
     }
+
+    #[quickcheck]
+    fn ord_consistent(a: ChainI, b: ChainI, c: ChainI) -> bool {
+        let antisymmetric = a.cmp(&b) == b.cmp(&a).reverse() && (a.cmp(&b).is_eq() == (a == b));
+        let transitive = !(a <= b && b <= c) || a <= c;
+        antisymmetric && transitive
+    }
+
+    #[test]
+    fn ord_elements_tiebreak() {
+        let mut v =
+            [ChainI::new(5, vec![2, 0]), ChainI::new(3, vec![2, 0]), ChainI::new(3, vec![1])];
+        v.sort();
+        assert_eq!(v[0], ChainI::new(3, vec![1]));
+        assert_eq!(v[1], ChainI::new(3, vec![2, 0]));
+        assert_eq!(v[2], ChainI::new(5, vec![2, 0]));
+    }
+
+    #[quickcheck]
+    fn try_to_specific_is_winner(b: ChainI) -> bool {
+        let r = b.as_ref();
+        match r.try_to_specific() {
+            None => r.is_empty(),
+            Some(s) => s.value() == r.winner() && s.elements() == r.elements(),
+        }
+    }
+
+    #[quickcheck]
+    fn push_rank_same_as_new(b: ChainI) -> bool {
+        let mut built = ChainI::new(b.elements, Vec::new());
+        let pushed = b.order.iter().all(|&c| built.push_rank(c).is_ok());
+        let duplicate = b.order.first().is_none_or(|&c| built.push_rank(c).is_err());
+        pushed && duplicate && built == b
+    }
 }
@@ -1,6 +1,12 @@
+use std::fmt;
+
+use rand::{Rng, seq::SliceRandom};
+
 use crate::{
     OrderRef,
-    chain::{ChainI, ChainRef},
+    chain::{Chain, ChainI, ChainRef},
+    orders::parse::write_groups,
+    specific::Specific,
     tied::TiedIRef,
     unique_and_bounded,
 };
@@ -45,6 +51,12 @@ impl<'a> ChainIRef<'a> {
         self.order
     }
 
+    /// Number of elements, including the ones not ranked by this order.
+    pub fn elements(&self) -> usize {
+        self.elements
+    }
+
+    /// Number of ranked elements.
     pub fn len(&self) -> usize {
         self.order.len()
     }
@@ -62,11 +74,74 @@ impl<'a> ChainIRef<'a> {
         self.order[0]
     }
 
+    /// Returns the top ranked element as a [`Specific`] over the same
+    /// elements, or [`None`] if no element is ranked.
+    pub fn try_to_specific(&self) -> Option<Specific> {
+        let &top = self.order.first()?;
+        Some(Specific::new(top, self.elements))
+    }
+
+    /// Returns `true` if both orders rank the same elements in the same order,
+    /// ignoring how many elements each order concerns. Use `==` to also compare
+    /// the number of elements.
+    ///
+    /// ```
+    /// use orders::chain::ChainIRef;
+    ///
+    /// let a = ChainIRef::new(5, &[2, 0]);
+    /// let b = ChainIRef::new(4, &[2, 0]);
+    /// assert!(a.same_ranking(b));
+    /// assert_ne!(a, b);
+    /// assert!(!a.same_ranking(ChainIRef::new(5, &[2])));
+    /// ```
+    pub fn same_ranking(&self, other: ChainIRef) -> bool {
+        self.order == other.order
+    }
+
+    /// Returns a complete order, ranking the elements of `self` first and then
+    /// every unranked element, from the lowest index to the highest.
+    ///
+    /// ```
+    /// use orders::chain::{Chain, ChainIRef};
+    ///
+    /// let order = ChainIRef::new(5, &[3, 1]);
+    /// assert_eq!(order.complete_sorted(), Chain::new(vec![3, 1, 0, 2, 4]));
+    /// ```
+    pub fn complete_sorted(&self) -> Chain {
+        let mut order = self.order.to_vec();
+        order.extend(self.unranked());
+        Chain { order }
+    }
+
+    /// Returns a complete order, ranking the elements of `self` first and then
+    /// every unranked element in a uniformly random order.
+    pub fn complete_random<R: Rng>(&self, rng: &mut R) -> Chain {
+        let mut order = self.order.to_vec();
+        order.extend(self.unranked());
+        order[self.len()..].shuffle(rng);
+        Chain { order }
+    }
+
+    /// Iterator over every element not ranked by `self`, in increasing order.
+    fn unranked(&self) -> impl Iterator<Item = usize> + use<> {
+        let mut ranked = vec![false; self.elements];
+        for &c in self.order {
+            ranked[c] = true;
+        }
+        ranked.into_iter().enumerate().filter(|(_, r)| !r).map(|(c, _)| c)
+    }
+
     pub fn to_tied(self, tied: &'a [bool]) -> TiedIRef<'a> {
         TiedIRef::new(self.elements, self.order, tied)
     }
 }
 
+impl fmt::Display for ChainIRef<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_groups(f, self.order, &[])
+    }
+}
+
 impl OrderRef for ChainIRef<'_> {
     type Owned = ChainI;
 
@@ -22,6 +22,8 @@
 
 mod complete;
 mod incomplete;
+mod reader;
 
 pub use complete::{Chain, ChainRef};
 pub use incomplete::{ChainI, ChainIRef};
+pub use reader::{ChainReader, ReadError};
@@ -0,0 +1,142 @@
+use std::{
+    error, fmt,
+    io::{self, BufRead},
+};
+
+use crate::{ParseError, chain::Chain};
+
+/// Error returned by [`ChainReader`].
+#[derive(Debug)]
+pub enum ReadError {
+    /// Failed to read from the underlying reader.
+    Io(io::Error),
+
+    /// The first line is not the number of elements.
+    Header(String),
+
+    /// A line could not be parsed as an order.
+    Parse(ParseError),
+
+    /// A line is an order of a different number of elements than the header.
+    Elements(usize),
+}
+
+impl fmt::Display for ReadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReadError::Io(e) => write!(f, "failed to read: {e}"),
+            ReadError::Header(s) => write!(f, "invalid number of elements: {s:?}"),
+            ReadError::Parse(e) => write!(f, "invalid order: {e}"),
+            ReadError::Elements(n) => write!(f, "order has {n} elements"),
+        }
+    }
+}
+
+impl error::Error for ReadError {}
+
+impl From<io::Error> for ReadError {
+    fn from(e: io::Error) -> Self {
+        ReadError::Io(e)
+    }
+}
+
+impl From<ParseError> for ReadError {
+    fn from(e: ParseError) -> Self {
+        ReadError::Parse(e)
+    }
+}
+
+/// Iterator reading one [`Chain`] at a time from a reader, so the orders
+/// never have to be in memory at the same time.
+///
+/// The first line is the number of elements, and every following non-empty
+/// line is an order in the syntax used by
+/// [`Chain::from_str`](std::str::FromStr), such as `2 > 0 > 1`.
+///
+/// ```
+/// use orders::chain::{Chain, ChainReader};
+///
+/// let text = "3\n2 > 0 > 1\n\n0 > 1 > 2\n";
+/// let reader = ChainReader::new(text.as_bytes()).unwrap();
+/// assert_eq!(reader.elements(), 3);
+/// let orders: Vec<Chain> = reader.collect::<Result<_, _>>().unwrap();
+/// assert_eq!(orders, [Chain::new(vec![2, 0, 1]), Chain::new(vec![0, 1, 2])]);
+/// ```
+pub struct ChainReader<R> {
+    reader: R,
+    elements: usize,
+    line: String,
+}
+
+impl<R: BufRead> ChainReader<R> {
+    /// Create a reader, consuming the header line of `reader`.
+    pub fn new(mut reader: R) -> Result<Self, ReadError> {
+        let mut line = String::new();
+        reader.read_line(&mut line)?;
+        let elements = line.trim().parse().map_err(|_| ReadError::Header(line.clone()))?;
+        Ok(ChainReader { reader, elements, line })
+    }
+
+    /// Number of elements of every order, as given by the header.
+    pub fn elements(&self) -> usize {
+        self.elements
+    }
+}
+
+impl<R: BufRead> Iterator for ChainReader<R> {
+    type Item = Result<Chain, ReadError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            self.line.clear();
+            match self.reader.read_line(&mut self.line) {
+                Ok(0) => return None,
+                Ok(_) if self.line.trim().is_empty() => continue,
+                Ok(_) => break,
+                Err(e) => return Some(Err(e.into())),
+            }
+        }
+        let order = match self.line.parse::<Chain>() {
+            Ok(order) => order,
+            Err(e) => return Some(Err(e.into())),
+        };
+        if order.order.len() == self.elements {
+            Some(Ok(order))
+        } else {
+            Some(Err(ReadError::Elements(order.order.len())))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{SeedableRng, rngs::StdRng};
+
+    use super::*;
+    use crate::collections::{ChainDense, DenseOrders};
+
+    #[quickcheck]
+    fn same_as_parsing_lines(elements: u8, len: u8, seed: u64) -> bool {
+        let elements = usize::from(elements % 20) + 1;
+        let mut orders = ChainDense::new(elements);
+        orders.generate_uniform(&mut StdRng::seed_from_u64(seed), usize::from(len));
+        let mut text = format!("{elements}\n");
+        for order in orders.iter() {
+            text.push_str(&format!("{order}\n"));
+        }
+        let parsed: Vec<Chain> = text.lines().skip(1).map(|l| l.parse().unwrap()).collect();
+        let read: Vec<Chain> =
+            ChainReader::new(text.as_bytes()).unwrap().collect::<Result<_, _>>().unwrap();
+        read == parsed && read == orders.to_owned_vec()
+    }
+
+    #[test]
+    fn errors() {
+        assert!(matches!(ChainReader::new("x\n".as_bytes()), Err(ReadError::Header(_))));
+        let mut reader = ChainReader::new("2\n1 > 0\n0\n0 > 0\n".as_bytes()).unwrap();
+        assert_eq!(reader.next().unwrap().unwrap(), Chain::new(vec![1, 0]));
+        assert!(matches!(reader.next(), Some(Err(ReadError::Elements(1)))));
+        assert!(matches!(reader.next(), Some(Err(ReadError::Parse(ParseError::Repeated(0))))));
+        assert!(reader.next().is_none());
+    }
+}
@@ -0,0 +1,125 @@
+//! Distances between orders of the same kind, for use in generic code such as
+//! clustering.
+
+use crate::{binary::BinaryRef, chain::ChainRef, tied::TiedRef};
+
+/// A distance between two orders of the same kind and number of elements.
+/// Identical orders have a distance of 0.
+pub trait Distance {
+    /// Returns the distance between `self` and `other`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the orders have a different number of elements.
+    fn distance(&self, other: &Self) -> f64;
+}
+
+impl Distance for ChainRef<'_> {
+    /// The [Kendall tau distance](ChainRef::kendall_distance), i.e. the number
+    /// of pairs of elements ordered differently.
+    fn distance(&self, other: &Self) -> f64 {
+        self.kendall_distance(*other) as f64
+    }
+}
+
+impl Distance for BinaryRef<'_> {
+    /// The [Hamming distance](https://en.wikipedia.org/wiki/Hamming_distance),
+    /// i.e. the number of elements approved by only one of the orders.
+    fn distance(&self, other: &Self) -> f64 {
+        assert!(self.elements() == other.elements());
+        self.values().iter().zip(other.values()).filter(|(a, b)| a != b).count() as f64
+    }
+}
+
+impl Distance for TiedRef<'_> {
+    /// `(1 - tau_b) / 2`, where `tau_b` is [Kendall's
+    /// tau-b](TiedRef::kendall_tau_b). This is 0 for identical orders and 1
+    /// for reversed orders. `tau_b` is undefined if either order ties every
+    /// element, so then the distance is 0 if both orders do, and `NaN`
+    /// otherwise.
+    fn distance(&self, other: &Self) -> f64 {
+        assert!(self.elements() == other.elements());
+        let all_tied = |order: &TiedRef| order.tied().iter().all(|&t| t);
+        if all_tied(self) && all_tied(other) {
+            return 0.0;
+        }
+        (1.0 - self.kendall_tau_b(*other)) / 2.0
+    }
+}
+
+/// Returns a symmetric matrix `m`, where `m[i][j]` is the distance between
+/// `items[i]` and `items[j]`.
+///
+/// # Panics
+///
+/// Panics if the items have a different number of elements.
+///
+/// ```
+/// use orders::{chain::ChainRef, distance::pairwise_distances};
+///
+/// let orders = [ChainRef::new(&[0, 1, 2]), ChainRef::new(&[2, 1, 0])];
+/// assert_eq!(pairwise_distances(&orders), [[0.0, 3.0], [3.0, 0.0]]);
+/// ```
+pub fn pairwise_distances<T: Distance>(items: &[T]) -> Vec<Vec<f64>> {
+    let mut out = vec![vec![0.0; items.len()]; items.len()];
+    for (i, a) in items.iter().enumerate() {
+        for (j, b) in items.iter().enumerate().skip(i + 1) {
+            let d = a.distance(b);
+            out[i][j] = d;
+            out[j][i] = d;
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{OrderOwned, binary::Binary, chain::Chain, tied::Tied};
+
+    #[quickcheck]
+    fn identical(chain: Chain, binary: Binary, tied: Tied) -> bool {
+        chain.as_ref().distance(&chain.as_ref()) == 0.0
+            && binary.as_ref().distance(&binary.as_ref()) == 0.0
+            && tied.as_ref().distance(&tied.as_ref()) == 0.0
+    }
+
+    #[test]
+    fn hamming() {
+        let a = BinaryRef::new(&[true, false, true, true]);
+        let b = BinaryRef::new(&[false, false, true, false]);
+        assert_eq!(a.distance(&b), 2.0);
+    }
+
+    #[test]
+    fn tied_reversed() {
+        let a = Tied::new(vec![0, 1, 2], vec![false, true]);
+        let b = Tied::new(vec![2, 1, 0], vec![true, false]);
+        assert_eq!(a.as_ref().distance(&b.as_ref()), 1.0);
+    }
+
+    #[test]
+    fn tied_all_tied() {
+        let a = Tied::new(vec![0, 1, 2], vec![true, true]);
+        let b = Tied::new(vec![2, 0, 1], vec![true, true]);
+        let c = Tied::new(vec![0, 1, 2], vec![false, true]);
+        assert_eq!(a.as_ref().distance(&b.as_ref()), 0.0);
+        assert!(a.as_ref().distance(&c.as_ref()).is_nan());
+    }
+
+    #[quickcheck]
+    fn pairwise_symmetric(orders: Vec<Binary>, elements: u8) -> bool {
+        let elements = elements as usize % 8;
+        let refs: Vec<BinaryRef> =
+            orders.iter().filter(|o| o.as_ref().len() == elements).map(|o| o.as_ref()).collect();
+        let m = pairwise_distances(&refs);
+        (0..refs.len())
+            .all(|i| (0..refs.len()).all(|j| m[i][j] == m[j][i] && (i != j || m[i][j] == 0.0)))
+    }
+
+    #[test]
+    #[should_panic]
+    fn different_elements() {
+        BinaryRef::new(&[true]).distance(&BinaryRef::new(&[true, false]));
+    }
+}
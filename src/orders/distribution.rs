@@ -0,0 +1,119 @@
+//! Probability distributions of orders, used to generate collections with
+//! methods like
+//! [`ChainDense::generate`](crate::collections::ChainDense::generate) and
+//! [`TiedDense::generate`](crate::collections::TiedDense::generate).
+
+use rand::Rng;
+
+use crate::{Order, chain::Chain, tied::Tied};
+
+/// A probability distribution of orders of type `O`.
+pub trait OrderDistribution<O> {
+    /// Sample an order of `elements` elements, using random numbers from `rng`.
+    fn sample<R: Rng>(&self, rng: &mut R, elements: usize) -> O;
+}
+
+/// Distribution where every order is equally likely.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Uniform;
+
+impl OrderDistribution<Chain> for Uniform {
+    fn sample<R: Rng>(&self, rng: &mut R, elements: usize) -> Chain {
+        Chain::random(rng, elements)
+    }
+}
+
+impl OrderDistribution<Tied> for Uniform {
+    fn sample<R: Rng>(&self, rng: &mut R, elements: usize) -> Tied {
+        Tied::random(rng, elements)
+    }
+}
+
+/// A [Mallows distribution](https://en.wikipedia.org/wiki/Mallows_model). The
+/// probability of an order is proportional to `phi^d`, where `d` is its
+/// [Kendall tau distance](crate::chain::ChainRef::kendall_distance) to
+/// `center`. A `phi` of `1.0` is the same distribution as [`Uniform`], while a
+/// `phi` close to `0.0` will mostly generate `center`.
+///
+/// The orders are sampled using the repeated insertion method.
+#[derive(Debug, Clone)]
+pub struct Mallows {
+    center: Chain,
+    // `cumulative[k]` is the sum of `phi^i` for `i` in `0..=k`.
+    cumulative: Vec<f64>,
+}
+
+impl Mallows {
+    /// # Panics
+    ///
+    /// Panics if `phi` is not in `(0, 1]`.
+    pub fn new(center: Chain, phi: f64) -> Self {
+        assert!(0.0 < phi && phi <= 1.0);
+        let mut cumulative: Vec<f64> = Vec::with_capacity(center.elements());
+        let mut power = 1.0;
+        let mut sum = 0.0;
+        for _ in 0..center.elements() {
+            sum += power;
+            cumulative.push(sum);
+            power *= phi;
+        }
+        Mallows { center, cumulative }
+    }
+}
+
+impl OrderDistribution<Chain> for Mallows {
+    /// # Panics
+    ///
+    /// Panics if `elements` is not the number of elements of the center.
+    fn sample<R: Rng>(&self, rng: &mut R, elements: usize) -> Chain {
+        assert!(elements == self.center.elements());
+        let mut order: Vec<usize> = Vec::with_capacity(elements);
+        for (i, &el) in self.center.order.iter().enumerate() {
+            // Inserting the element `k` steps above the bottom of `order` adds `k`
+            // to the distance from `center`, so we choose `k` with probability
+            // proportional to `phi^k`.
+            let u: f64 = rng.random::<f64>() * self.cumulative[i];
+            let k = self.cumulative[..i].partition_point(|&c| c <= u);
+            order.insert(i - k, el);
+        }
+        Chain { order }
+    }
+}
+
+/// A Plackett–Luce distribution. Each order is built from the top down, where
+/// the next element is chosen with a probability proportional to its weight
+/// among the remaining elements. If all weights are equal, then this is the
+/// same distribution as [`Uniform`].
+#[derive(Debug, Clone)]
+pub struct PlackettLuce {
+    weights: Vec<f64>,
+}
+
+impl PlackettLuce {
+    /// # Panics
+    ///
+    /// Panics if any weight is not positive and finite.
+    pub fn new(weights: Vec<f64>) -> Self {
+        assert!(weights.iter().all(|w| w.is_finite() && *w > 0.0));
+        PlackettLuce { weights }
+    }
+}
+
+impl OrderDistribution<Chain> for PlackettLuce {
+    /// # Panics
+    ///
+    /// Panics if `elements` is not the number of weights.
+    fn sample<R: Rng>(&self, rng: &mut R, elements: usize) -> Chain {
+        assert!(elements == self.weights.len());
+        // Giving each element an exponentially distributed key with rate equal to
+        // its weight, and sorting by the keys, is the same as sampling every
+        // element one at a time.
+        let mut keys: Vec<(f64, usize)> = Vec::with_capacity(elements);
+        for (i, w) in self.weights.iter().enumerate() {
+            let u: f64 = rng.random();
+            keys.push((-(1.0 - u).ln() / w, i));
+        }
+        keys.sort_by(|a, b| a.0.total_cmp(&b.0));
+        Chain { order: keys.into_iter().map(|(_, i)| i).collect() }
+    }
+}
@@ -1,11 +1,17 @@
 use crate::partial_order;
 
 pub mod binary;
+mod bytes;
 pub mod cardinal;
 pub mod chain;
+pub mod distance;
+pub mod distribution;
+mod parse;
 pub mod specific;
 pub mod tied;
 
+pub use parse::ParseError;
+
 pub trait Order {
     /// The number of elements that can be in this order.
     fn elements(&self) -> usize;
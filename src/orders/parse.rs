@@ -0,0 +1,80 @@
+use std::fmt;
+
+/// Error returned when parsing an order from a string.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseError {
+    /// Part of the string could not be parsed as an element.
+    InvalidElement(String),
+
+    /// An element occurs more than once in the order.
+    Repeated(usize),
+
+    /// An element is missing from the order, even though a larger element is
+    /// part of it.
+    Missing(usize),
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseError::InvalidElement(s) => write!(f, "invalid element: {s:?}"),
+            ParseError::Repeated(n) => write!(f, "element {n} occurs more than once"),
+            ParseError::Missing(n) => write!(f, "element {n} is missing"),
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Write `order` in the syntax read by [`parse_groups`], where `tied` denotes
+/// whether each element is tied with the next one. Missing values in `tied`
+/// are treated as `false`.
+pub(crate) fn write_groups(
+    f: &mut fmt::Formatter<'_>,
+    order: &[usize],
+    tied: &[bool],
+) -> fmt::Result {
+    for (i, e) in order.iter().enumerate() {
+        if i != 0 {
+            f.write_str(if tied.get(i - 1) == Some(&true) { " = " } else { " > " })?;
+        }
+        write!(f, "{e}")?;
+    }
+    Ok(())
+}
+
+/// Parse a complete order such as `2>0=1`, where `>` separates groups and `=`
+/// separates tied elements in a group (only if `ties` is true). Returns the
+/// elements and whether each element is tied with the next one.
+pub(crate) fn parse_groups(s: &str, ties: bool) -> Result<(Vec<usize>, Vec<bool>), ParseError> {
+    let mut order = Vec::new();
+    let mut tied = Vec::new();
+    if s.trim().is_empty() {
+        return Ok((order, tied));
+    }
+    for group in s.split('>') {
+        if !order.is_empty() {
+            tied.push(false);
+        }
+        let members: Vec<&str> = if ties { group.split('=').collect() } else { vec![group] };
+        for (i, member) in members.into_iter().enumerate() {
+            if i != 0 {
+                tied.push(true);
+            }
+            let token = member.trim();
+            let n = token.parse().map_err(|_| ParseError::InvalidElement(token.to_string()))?;
+            order.push(n);
+        }
+    }
+
+    let mut sorted = order.clone();
+    sorted.sort_unstable();
+    for (i, &n) in sorted.iter().enumerate() {
+        if i != 0 && sorted[i - 1] == n {
+            return Err(ParseError::Repeated(n));
+        } else if n != i {
+            return Err(ParseError::Missing(i));
+        }
+    }
+    Ok((order, tied))
+}
@@ -23,6 +23,11 @@ impl Specific {
         let value = rng.random_range(0..elements);
         Self { value, elements }
     }
+
+    /// The chosen element.
+    pub fn value(&self) -> usize {
+        self.value
+    }
 }
 
 impl Order for Specific {
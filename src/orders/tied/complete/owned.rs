@@ -1,15 +1,21 @@
+use std::{fmt, str::FromStr};
+
 use rand::{Rng, distr::Bernoulli, prelude::SliceRandom};
 
 use crate::{
-    Order, OrderOwned,
-    orders::cardinal::CardinalRef,
+    Order, OrderOwned, ParseError,
+    orders::{
+        bytes::{read_bits, read_varints, write_bits, write_varint},
+        cardinal::CardinalRef,
+        parse::parse_groups,
+    },
     partial_order::PartialOrderManual,
     tied::{TiedI, TiedRef},
     unique_and_bounded,
 };
 
 /// An order with possible ties
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, PartialEq, Eq, Hash)]
 pub struct Tied {
     order: Vec<usize>,
     tied: Vec<bool>,
@@ -65,6 +71,42 @@ impl Tied {
         &self.tied
     }
 
+    /// Encode the order in a compact byte format, read by
+    /// [`Tied::from_bytes`].
+    ///
+    /// The layout is the same as
+    /// [`Chain::to_bytes`](crate::chain::Chain::to_bytes), followed by
+    /// [`Tied::tied`] as a bitmap: 8 values per byte, starting
+    /// from the least significant bit, where unused bits in the last byte
+    /// are zero. The number of elements is not included.
+    ///
+    /// ```
+    /// use orders::tied::Tied;
+    ///
+    /// let order = Tied::new(vec![2, 0, 1], vec![true, false]);
+    /// assert_eq!(order.to_bytes(), [2, 0, 1, 0b01]);
+    /// assert_eq!(Tied::from_bytes(&order.to_bytes(), 3), Some(order));
+    /// ```
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out = Vec::with_capacity(self.order.len() + self.tied.len().div_ceil(8));
+        for &c in &self.order {
+            write_varint(&mut out, c);
+        }
+        write_bits(&mut out, &self.tied);
+        out
+    }
+
+    /// Decode an order of `elements` written by [`Tied::to_bytes`].
+    ///
+    /// Returns [`None`] if `bytes` isn't exactly the encoding of an order of
+    /// `elements`.
+    pub fn from_bytes(bytes: &[u8], elements: usize) -> Option<Tied> {
+        let mut bytes = bytes;
+        let order = read_varints(&mut bytes, elements)?;
+        let tied = read_bits(bytes, elements.saturating_sub(1))?;
+        Tied::try_new(order, tied)
+    }
+
     /// Clones from `source` to `self`, similar to [`Clone::clone_from`].
     pub fn clone_from_ref(&mut self, source: TiedRef) {
         self.order.clone_from_slice(source.order());
@@ -89,6 +131,68 @@ impl Tied {
         Tied::new(order, tied)
     }
 
+    /// Tie group `group_index` with the group after it, where `0` is the
+    /// highest group.
+    ///
+    /// Returns `Err` if there is no group after `group_index`.
+    ///
+    /// ```
+    /// use orders::tied::Tied;
+    ///
+    /// let mut rank = Tied::new(vec![2, 0, 1], vec![false, false]);
+    /// rank.merge_groups(1).unwrap();
+    /// assert_eq!(rank.tied(), &[false, true]);
+    /// assert!(rank.merge_groups(1).is_err());
+    /// ```
+    pub fn merge_groups(&mut self, group_index: usize) -> Result<(), &'static str> {
+        let boundary = self
+            .tied
+            .iter()
+            .enumerate()
+            .filter(|(_, t)| !**t)
+            .nth(group_index)
+            .map(|(i, _)| i)
+            .ok_or("Group index out of range")?;
+        self.tied[boundary] = true;
+        Ok(())
+    }
+
+    /// Split a group in two, so that the element at `position` in
+    /// [`Tied::order`] is no longer tied with the element after it.
+    ///
+    /// Returns `Err` if there is no element after `position`.
+    pub fn split_group(&mut self, position: usize) -> Result<(), &'static str> {
+        let tie = self.tied.get_mut(position).ok_or("Position out of range")?;
+        *tie = false;
+        Ok(())
+    }
+
+    /// Returns `true` if `self` and `other` have the same groups of tied
+    /// elements in the same order, even if the elements within a group are
+    /// listed in a different order. Use `==` to also compare the order within
+    /// groups.
+    ///
+    /// ```
+    /// use orders::tied::Tied;
+    ///
+    /// let a = Tied::new(vec![2, 0, 1], vec![true, false]);
+    /// let b = Tied::new(vec![0, 2, 1], vec![true, false]);
+    /// assert!(a.semantically_eq(&b));
+    /// assert_ne!(a, b);
+    /// ```
+    pub fn semantically_eq(&self, other: &Tied) -> bool {
+        let ranks = |order: &Tied| {
+            let mut rank = vec![0; order.order.len()];
+            for (i, group) in order.as_ref().iter_groups_ranked() {
+                for &c in group {
+                    rank[c] = i;
+                }
+            }
+            rank
+        };
+        self.order.len() == other.order.len() && ranks(self) == ranks(other)
+    }
+
     /// Generate a random tied ranking of `elements`.
     pub fn random<R: Rng>(rng: &mut R, elements: usize) -> Self {
         if elements == 0 {
@@ -116,6 +220,26 @@ impl<'a> From<CardinalRef<'a>> for Tied {
     }
 }
 
+impl FromStr for Tied {
+    type Err = ParseError;
+
+    /// Parse an order of elements separated by `>`, from highest to lowest,
+    /// where tied elements are separated by `=`. The number of elements is the
+    /// number of elements in the string.
+    ///
+    /// ```
+    /// use orders::tied::Tied;
+    ///
+    /// let order: Tied = "2 > 0 = 1".parse().unwrap();
+    /// assert_eq!(order.order(), [2, 0, 1]);
+    /// assert_eq!(order.tied(), [false, true]);
+    /// ```
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (order, tied) = parse_groups(s, true)?;
+        Ok(Tied { order, tied })
+    }
+}
+
 impl Order for Tied {
     fn elements(&self) -> usize {
         self.order.len()
@@ -143,6 +267,12 @@ impl Order for Tied {
     }
 }
 
+impl fmt::Display for Tied {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.as_ref().fmt(f)
+    }
+}
+
 impl<'a> OrderOwned<'a> for Tied {
     type Ref = TiedRef<'a>;
 
@@ -206,4 +336,133 @@ mod tests {
     fn partial(orders: Tied) -> bool {
         partial_order::tests::valid(&orders.to_partial())
     }
+
+    #[quickcheck]
+    fn merge_all_groups(mut orders: Tied) -> bool {
+        while orders.merge_groups(0).is_ok() {}
+        valid(&orders) && orders.tied.iter().all(|t| *t)
+    }
+
+    #[quickcheck]
+    fn split_merge(mut orders: Tied, i: usize) -> bool {
+        if orders.tied.is_empty() {
+            return orders.split_group(i).is_err();
+        }
+        let i = i % orders.tied.len();
+        orders.split_group(i).unwrap();
+        let groups_before = orders.as_ref().iter_groups().count();
+        let group = orders.as_ref().iter_groups().position(|g| g.contains(&orders.order[i]));
+        orders.merge_groups(group.unwrap()).unwrap();
+        valid(&orders)
+            && orders.tied[i]
+            && orders.as_ref().iter_groups().count() + 1 == groups_before
+    }
+
+    #[quickcheck]
+    fn kendall_tau_b_extremes(orders: Tied) -> bool {
+        let mut reversed = orders.clone();
+        reversed.order.reverse();
+        reversed.tied.reverse();
+        let same = orders.as_ref().kendall_tau_b(orders.as_ref());
+        let opposite = orders.as_ref().kendall_tau_b(reversed.as_ref());
+        if orders.tied.iter().all(|&t| t) {
+            same.is_nan() && opposite.is_nan()
+        } else {
+            (same - 1.0).abs() < 1e-12 && (opposite + 1.0).abs() < 1e-12
+        }
+    }
+
+    // Scores (1, 2, 2, 3) and (1, 1, 2, 3) have 4 concordant pairs, no
+    // discordant pairs and one tie in each, so tau-b is 4 / sqrt(5 * 5).
+    #[test]
+    fn kendall_tau_b_example() {
+        let a = Tied::new(vec![3, 1, 2, 0], vec![false, true, false]);
+        let b = Tied::new(vec![3, 2, 0, 1], vec![false, false, true]);
+        assert!((a.as_ref().kendall_tau_b(b.as_ref()) - 0.8).abs() < 1e-12);
+
+        // One discordant pair out of three: (2 - 1) / 3
+        let c = Tied::new(vec![0, 1, 2], vec![false, false]);
+        let d = Tied::new(vec![1, 0, 2], vec![false, false]);
+        assert!((c.as_ref().kendall_tau_b(d.as_ref()) - 1.0 / 3.0).abs() < 1e-12);
+    }
+
+    #[quickcheck]
+    fn ref_len(orders: Tied) -> bool {
+        let r = orders.as_ref();
+        r.len() == r.elements() && r.is_empty() == (r.elements() == 0)
+    }
+
+    #[quickcheck]
+    fn group_of_monotonic(orders: Tied) -> bool {
+        let r = orders.as_ref();
+        let groups: Vec<usize> = r.order().iter().map(|&c| r.group_of(c).unwrap()).collect();
+        let same_group = r.tied().iter().zip(groups.windows(2)).all(|(t, w)| *t == (w[0] == w[1]));
+        groups.first().is_none_or(|g| *g == 0) && groups.is_sorted() && same_group
+    }
+
+    #[test]
+    fn from_str() {
+        let order: Tied = "3=1>0>2=4".parse().unwrap();
+        assert!(valid(&order));
+        assert_eq!(order, Tied::new(vec![3, 1, 0, 2, 4], vec![true, false, false, true]));
+        assert_eq!("0=0".parse::<Tied>().unwrap_err(), ParseError::Repeated(0));
+        assert_eq!("0=x".parse::<Tied>().unwrap_err(), ParseError::InvalidElement("x".to_string()));
+        assert_eq!("1>2".parse::<Tied>().unwrap_err(), ParseError::Missing(0));
+    }
+
+    #[quickcheck]
+    fn display_round_trip(order: Tied) -> bool {
+        order.to_string().parse::<Tied>() == Ok(order)
+    }
+
+    #[test]
+    fn display() {
+        let order = Tied::new(vec![3, 1, 0, 2], vec![true, false, false]);
+        assert_eq!(order.to_string(), "3 = 1 > 0 > 2");
+        let order = TiedI::new(5, vec![4, 1], vec![true]);
+        assert_eq!(order.to_string(), "4 = 1");
+    }
+
+    #[test]
+    fn merge_groups_out_of_range() {
+        let mut rank = Tied::new_tied(4);
+        assert!(rank.merge_groups(0).is_err());
+        assert!(rank.split_group(3).is_err());
+    }
+
+    #[quickcheck]
+    fn bytes_round_trip(order: Tied) -> bool {
+        Tied::from_bytes(&order.to_bytes(), order.elements()) == Some(order)
+    }
+
+    #[test]
+    fn from_bytes_corrupt() {
+        assert_eq!(Tied::from_bytes(&[1, 0, 0], 2), Some(Tied::new(vec![1, 0], vec![false])));
+        assert_eq!(Tied::from_bytes(&[1, 0, 0b10], 2), None);
+        assert_eq!(Tied::from_bytes(&[1, 1, 0], 2), None);
+        assert_eq!(Tied::from_bytes(&[1, 0], 2), None);
+        assert_eq!(Tied::from_bytes(&[0], 1), Some(Tied::new(vec![0], vec![])));
+    }
+
+    #[quickcheck]
+    fn semantically_eq_shuffled_groups(order: Tied, seed: u64) -> bool {
+        use rand::{SeedableRng, rngs::StdRng};
+
+        let rng = &mut StdRng::seed_from_u64(seed);
+        let mut shuffled = Vec::with_capacity(order.order.len());
+        for group in order.as_ref().iter_groups() {
+            let start = shuffled.len();
+            shuffled.extend_from_slice(group);
+            shuffled[start..].shuffle(rng);
+        }
+        let other = Tied::new(shuffled, order.tied.clone());
+        let split = order.tied.iter().position(|&t| t).map(|p| {
+            let mut split = order.clone();
+            split.split_group(p).unwrap();
+            split
+        });
+        order.semantically_eq(&other)
+            && other.semantically_eq(&order)
+            && split.is_none_or(|split| !split.semantically_eq(&order))
+    }
 }
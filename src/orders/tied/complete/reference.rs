@@ -1,11 +1,15 @@
+use std::{cmp::Ordering, fmt};
+
 use crate::{
     OrderRef,
+    orders::parse::write_groups,
     specific::Specific,
     tied::{GroupIterator, Tied, TiedIRef, split_ref::SplitRef},
     unique_and_bounded,
 };
 
 /// Reference to [`Tied`]
+#[derive(Clone, Copy)]
 pub struct TiedRef<'a> {
     order_tied: SplitRef<'a>,
 }
@@ -47,6 +51,16 @@ impl<'a> TiedRef<'a> {
         self.order().len()
     }
 
+    /// Length of [`TiedRef::order`], which contains every element, including
+    /// tied ones.
+    pub fn len(&self) -> usize {
+        self.order().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
     pub fn order(&self) -> &'a [usize] {
         self.order_tied.a()
     }
@@ -66,6 +80,78 @@ impl<'a> TiedRef<'a> {
     pub fn iter_groups(&self) -> GroupIterator<'_> {
         TiedIRef::from(self).iter_groups()
     }
+
+    /// Iterate over the groups of tied elements in the order together with
+    /// their rank. See [`TiedIRef::iter_groups_ranked`].
+    pub fn iter_groups_ranked(&self) -> impl Iterator<Item = (usize, &'a [usize])> + use<'a> {
+        TiedIRef::from(self).iter_groups_ranked()
+    }
+
+    /// [Kendall's tau-b](https://en.wikipedia.org/wiki/Kendall_rank_correlation_coefficient#Tau-b)
+    /// between `self` and `other`, a correlation between -1 and 1 which
+    /// corrects for pairs of elements tied in either order. Identical orders
+    /// give 1 and reversed orders give -1.
+    ///
+    /// Returns `NaN` if either order ties every pair of elements, as the
+    /// correlation is then undefined.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the orders have a different number of elements.
+    ///
+    /// ```
+    /// use orders::tied::TiedRef;
+    ///
+    /// let a = TiedRef::new(&[3, 1, 2, 0], &[false, true, false]);
+    /// let b = TiedRef::new(&[3, 2, 0, 1], &[false, false, true]);
+    /// assert!((a.kendall_tau_b(b) - 0.8).abs() < 1e-12);
+    /// ```
+    pub fn kendall_tau_b(&self, other: TiedRef) -> f64 {
+        assert!(self.elements() == other.elements());
+        let group = |order: &TiedRef| {
+            let mut group = vec![0; order.elements()];
+            for (i, g) in order.iter_groups().enumerate() {
+                for &c in g {
+                    group[c] = i;
+                }
+            }
+            group
+        };
+        let (a, b) = (group(self), group(&other));
+        let (mut concordant, mut discordant) = (0, 0);
+        let (mut tied_a, mut tied_b) = (0, 0);
+        let n = self.elements();
+        for i in 0..n {
+            for j in (i + 1)..n {
+                match (a[i].cmp(&a[j]), b[i].cmp(&b[j])) {
+                    (Ordering::Equal, Ordering::Equal) => {
+                        tied_a += 1;
+                        tied_b += 1;
+                    }
+                    (Ordering::Equal, _) => tied_a += 1,
+                    (_, Ordering::Equal) => tied_b += 1,
+                    (x, y) if x == y => concordant += 1,
+                    _ => discordant += 1,
+                }
+            }
+        }
+        let pairs = (n * n.saturating_sub(1) / 2) as f64;
+        let denominator = ((pairs - tied_a as f64) * (pairs - tied_b as f64)).sqrt();
+        (concordant as f64 - discordant as f64) / denominator
+    }
+
+    /// Returns group of element `c`. `0` is highest rank. Takes `O(n)` time.
+    ///
+    /// See [`TiedIRef::group_of`].
+    pub fn group_of(&self, c: usize) -> Option<usize> {
+        TiedIRef::from(self).group_of(c)
+    }
+}
+
+impl fmt::Display for TiedRef<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_groups(f, self.order(), self.tied())
+    }
 }
 
 impl<'a> OrderRef for TiedRef<'a> {
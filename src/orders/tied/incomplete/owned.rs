@@ -1,4 +1,4 @@
-use std::iter::repeat_n;
+use std::{fmt, iter::repeat_n};
 
 use rand::{
     Rng,
@@ -7,13 +7,15 @@ use rand::{
 };
 
 use crate::{
-    OrderOwned, add_bool, sort_using,
+    OrderOwned, add_bool,
+    chain::ChainI,
+    sort_using,
     tied::{Tied, TiedIRef},
     unique_and_bounded,
 };
 
 /// Incomplete version of [`Tied`]
-#[derive(Debug, PartialEq, Eq, Default, PartialOrd, serde::Deserialize, serde::Serialize)]
+#[derive(Debug, PartialEq, Eq, Hash, Default, PartialOrd, serde::Deserialize, serde::Serialize)]
 pub struct TiedI {
     pub(crate) elements: usize,
     pub(crate) order: Vec<usize>,
@@ -72,6 +74,28 @@ impl TiedI {
         TiedI { elements, order, tied }
     }
 
+    /// Create a `TiedI` from a strict order `chain`, where `tied` indicates
+    /// which adjacent pairs in the order should be tied.
+    ///
+    /// Returns [`None`] if `tied` is not one shorter than the number of ranked
+    /// elements in `chain` (or empty, if `chain` is empty).
+    ///
+    /// ```
+    /// use orders::{chain::ChainI, tied::TiedI};
+    ///
+    /// let chain = ChainI::new(4, vec![3, 1, 0]);
+    /// let tied = TiedI::from_chain_i(chain, vec![true, false]).unwrap();
+    /// assert_eq!(tied, TiedI::from_slices(4, &[&[3, 1], &[0]]));
+    /// ```
+    pub fn from_chain_i(chain: ChainI, tied: Vec<bool>) -> Option<Self> {
+        let ChainI { elements, order } = chain;
+        if tied.len() + 1 == order.len() || tied.is_empty() && order.is_empty() {
+            Some(TiedI { elements, order, tied })
+        } else {
+            None
+        }
+    }
+
     pub fn new_tied_from_slice(elements: usize, order: &[usize]) -> Self {
         let tie_len = order.len().saturating_sub(1);
         let tied = vec![true; tie_len];
@@ -388,6 +412,12 @@ impl TiedI {
     }
 }
 
+impl fmt::Display for TiedI {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.as_ref().fmt(f)
+    }
+}
+
 impl<'a> OrderOwned<'a> for TiedI {
     type Ref = TiedIRef<'a>;
 
@@ -506,6 +536,44 @@ mod tests {
         o == new_order_group && t == new_tied_group
     }
 
+    #[quickcheck]
+    fn group_of_unranked(rank: TiedI) -> bool {
+        let r = rank.as_ref();
+        (0..rank.elements).all(|c| r.group_of(c).is_some() == rank.order.contains(&c))
+    }
+
+    #[quickcheck]
+    fn from_chain_i(chain: ChainI) -> bool {
+        let elements = chain.elements;
+        let order = chain.order.clone();
+        let tied = vec![false; order.len().saturating_sub(1)];
+        let too_long = vec![false; order.len() + 1];
+        TiedI::from_chain_i(chain.clone(), too_long).is_none()
+            && TiedI::from_chain_i(chain, tied.clone()) == Some(TiedI::new(elements, order, tied))
+    }
+
+    #[test]
+    fn hash_set() {
+        use std::collections::HashSet;
+
+        let mut set = HashSet::new();
+        set.insert(TiedI::new(4, vec![2, 0], vec![true]));
+        set.insert(TiedI::new(4, vec![2, 0], vec![true]));
+        set.insert(TiedI::new(4, vec![2, 0], vec![false]));
+        set.insert(TiedI::new(5, vec![2, 0], vec![true]));
+        set.insert(TiedI::new(4, vec![0, 2], vec![true]));
+        assert_eq!(set.len(), 4);
+        assert!(set.contains(&TiedI::new(5, vec![2, 0], vec![true])));
+    }
+
+    #[test]
+    fn from_chain_i_small() {
+        assert!(TiedI::from_chain_i(ChainI::new(3, vec![]), vec![]).is_some());
+        assert!(TiedI::from_chain_i(ChainI::new(3, vec![]), vec![true]).is_none());
+        assert!(TiedI::from_chain_i(ChainI::new(3, vec![2]), vec![]).is_some());
+        assert!(TiedI::from_chain_i(ChainI::new(3, vec![2, 0]), vec![]).is_none());
+    }
+
     #[quickcheck]
     fn keep_top_n_len(mut rank: TiedI, i: usize) -> bool {
         let n = if rank.len() == 0 { 0 } else { i % rank.len() };
@@ -514,4 +582,11 @@ mod tests {
         let l2 = rank.len();
         n <= l2 && l2 <= l1
     }
+
+    #[quickcheck]
+    fn iter_groups_ranked(order: TiedI) -> bool {
+        let order = order.as_ref();
+        order.iter_groups_ranked().map(|(rank, _)| rank).eq(0..order.iter_groups().count())
+            && order.iter_groups_ranked().map(|(_, group)| group).eq(order.iter_groups())
+    }
 }
@@ -1,8 +1,11 @@
+use std::fmt;
+
 use rand::seq::IndexedRandom;
 
 use super::groups::GroupIterator;
 use crate::{
     OrderRef,
+    orders::parse::write_groups,
     specific::Specific,
     tied::{TiedI, split_ref::SplitRef},
     unique_and_bounded,
@@ -105,6 +108,7 @@ impl<'a> TiedIRef<'a> {
         TiedIRef::new(self.elements, &self.order()[0..i], &self.tied()[0..(i.saturating_sub(1))])
     }
 
+    /// Number of ranked elements.
     pub fn len(&self) -> usize {
         self.order().len()
     }
@@ -123,6 +127,23 @@ impl<'a> TiedIRef<'a> {
         GroupIterator { order: *self }
     }
 
+    /// Iterate over the groups of tied elements in the order together with
+    /// their rank, starting with the highest elements at rank `0`.
+    ///
+    /// ```
+    /// use orders::tied::TiedIRef;
+    ///
+    /// let order = TiedIRef::new(7, &[4, 2, 3, 0, 1], &[true, false, false, true]);
+    /// let mut groups = order.iter_groups_ranked();
+    /// assert_eq!(groups.next(), Some((0, &[4, 2][..])));
+    /// assert_eq!(groups.next(), Some((1, &[3][..])));
+    /// assert_eq!(groups.next(), Some((2, &[0, 1][..])));
+    /// assert_eq!(groups.next(), None);
+    /// ```
+    pub fn iter_groups_ranked(&self) -> impl Iterator<Item = (usize, &'a [usize])> + use<'a> {
+        self.iter_groups().enumerate()
+    }
+
     /// Returns group of element `c`. `0` is highest rank. Takes `O(n)` time.
     ///
     /// ```
@@ -190,6 +211,12 @@ impl<'a> TiedIRef<'a> {
     }
 }
 
+impl fmt::Display for TiedIRef<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_groups(f, self.order(), self.tied())
+    }
+}
+
 impl<'a> OrderRef for TiedIRef<'a> {
     type Owned = TiedI;
 
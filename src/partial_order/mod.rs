@@ -1,12 +1,40 @@
-use std::cmp::Ordering;
+use std::{cmp::Ordering, fmt};
 
 use bool_matrix::MatrixBool;
 
-use super::Order;
+use super::{Order, unique_and_bounded};
+use crate::{chain::ChainI, collections::ChainDense, tied::Tied};
 
 mod bool_matrix;
 
-#[derive(Debug)]
+// The most elements `PartialOrder::completions` accepts. Its table then has
+// `2^24` entries, taking 128 MiB with 64-bit `usize`.
+const MAX_COMPLETION_ELEMENTS: usize = 24;
+
+/// Error returned when the given relations would make two distinct elements
+/// both smaller than each other.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CycleError {
+    a: usize,
+    b: usize,
+}
+
+impl CycleError {
+    /// Two distinct elements which are part of the same cycle.
+    pub fn elements(&self) -> (usize, usize) {
+        (self.a, self.b)
+    }
+}
+
+impl fmt::Display for CycleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "relations contain a cycle through {} and {}", self.a, self.b)
+    }
+}
+
+impl std::error::Error for CycleError {}
+
+#[derive(Debug, PartialEq, Eq)]
 pub struct PartialOrder {
     // 2D matrix of length n*n, order[a*len + b] is `true` if a ≤ b
     matrix: MatrixBool,
@@ -41,6 +69,55 @@ impl PartialOrder {
         Self { matrix: MatrixBool::from_vec(order, elements) }
     }
 
+    /// Create a partial order of `elements` from a row-major matrix, where
+    /// `less[a * elements + b]` is `true` if `a < b`. Relations implied by
+    /// transitivity are added and the diagonal is ignored.
+    ///
+    /// Returns `Err(CycleError)` if the transitive closure contains both `a <
+    /// b` and `b < a` for some distinct `a` and `b`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `less.len() != elements * elements`.
+    ///
+    /// ```
+    /// use orders::partial_order::PartialOrder;
+    ///
+    /// // 0 < 1 and 1 < 2
+    /// let less = [false, true, false, false, false, true, false, false, false];
+    /// let po = PartialOrder::from_matrix(3, &less).unwrap();
+    /// assert!(po.le(0, 2));
+    ///
+    /// let cycle = [false, true, true, false];
+    /// assert!(PartialOrder::from_matrix(2, &cycle).is_err());
+    /// ```
+    pub fn from_matrix(elements: usize, less: &[bool]) -> Result<Self, CycleError> {
+        assert!(less.len() == elements * elements);
+        let mut manual = PartialOrderManual::new(elements);
+        for a in 0..elements {
+            for b in 0..elements {
+                if a != b && less[a * elements + b] {
+                    manual.set(a, b);
+                }
+            }
+        }
+        manual.finish()
+    }
+
+    /// Returns the number of elements and a row-major matrix, where
+    /// `out[a * elements + b]` is `true` if `a < b`. This is the inverse of
+    /// [`PartialOrder::from_matrix`] for orders without equal elements.
+    pub fn to_matrix(&self) -> (usize, Vec<bool>) {
+        let n = self.elements();
+        let mut out = vec![false; n * n];
+        for a in 0..n {
+            for b in 0..n {
+                out[a * n + b] = self.le(a, b) && !self.le(b, a);
+            }
+        }
+        (n, out)
+    }
+
     /// Returns true if and only if `a ≤ b`.
     #[must_use]
     pub fn le(&self, a: usize, b: usize) -> bool {
@@ -118,6 +195,657 @@ impl PartialOrder {
         }
     }
 
+    /// Iterate over every pair of distinct elements `(a, b)` with `a < b`.
+    fn pairs(&self) -> impl Iterator<Item = (usize, usize)> + use<> {
+        let n = self.elements();
+        (0..n).flat_map(move |a| ((a + 1)..n).map(move |b| (a, b)))
+    }
+
+    /// Iterate over every unordered pair of distinct elements that are
+    /// comparable. Each pair `(a, b)` is only returned once, with `a < b`.
+    ///
+    /// ```
+    /// use orders::partial_order::PartialOrder;
+    ///
+    /// let mut po = PartialOrder::new_empty(3);
+    /// po.set(2, 0);
+    /// assert_eq!(po.comparable_pairs().collect::<Vec<_>>(), [(0, 2)]);
+    /// assert_eq!(po.incomparable_pairs().collect::<Vec<_>>(), [(0, 1), (1, 2)]);
+    /// ```
+    pub fn comparable_pairs(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.pairs().filter(|&(a, b)| self.ord(a, b).is_some())
+    }
+
+    /// Iterate over every unordered pair of distinct elements that are not
+    /// comparable. Each pair `(a, b)` is only returned once, with `a < b`.
+    pub fn incomparable_pairs(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.pairs().filter(|&(a, b)| self.ord(a, b).is_none())
+    }
+
+    /// Every element `x` with `x ≤ e`, including `e` itself, in increasing
+    /// index order.
+    ///
+    /// ```
+    /// use orders::partial_order::PartialOrder;
+    ///
+    /// // 0 < 1 < 2 and 3 < 2
+    /// let mut po = PartialOrder::new_empty(4);
+    /// po.set(0, 1);
+    /// po.set(1, 2);
+    /// po.set(0, 2);
+    /// po.set(3, 2);
+    /// assert_eq!(po.downset(2), [0, 1, 2, 3]);
+    /// assert_eq!(po.downset(0), [0]);
+    /// assert_eq!(po.upset(0), [0, 1, 2]);
+    /// ```
+    pub fn downset(&self, e: usize) -> Vec<usize> {
+        (0..self.elements()).filter(|&x| self.le(x, e)).collect()
+    }
+
+    /// Every element `x` with `e ≤ x`, including `e` itself, in increasing
+    /// index order.
+    pub fn upset(&self, e: usize) -> Vec<usize> {
+        (0..self.elements()).filter(|&x| self.le(e, x)).collect()
+    }
+
+    /// Every element `x` with `a ≤ x ≤ b`, in increasing index order. Empty if
+    /// `a ≰ b`. This includes every element equal to `a` or `b`, so
+    /// `interval(a, a)` is `[a]` only if no other element is equal to `a`.
+    ///
+    /// ```
+    /// use orders::partial_order::PartialOrder;
+    ///
+    /// // 0 < 1 < 2 and 3 < 2
+    /// let mut po = PartialOrder::new_empty(4);
+    /// po.set(0, 1);
+    /// po.set(1, 2);
+    /// po.set(0, 2);
+    /// po.set(3, 2);
+    /// assert_eq!(po.interval(0, 2), [0, 1, 2]);
+    /// assert_eq!(po.interval(3, 3), [3]);
+    /// assert!(po.interval(2, 0).is_empty());
+    /// ```
+    pub fn interval(&self, a: usize, b: usize) -> Vec<usize> {
+        if !self.le(a, b) {
+            return Vec::new();
+        }
+        (0..self.elements()).filter(|&x| self.le(a, x) && self.le(x, b)).collect()
+    }
+
+    /// The [Möbius function](https://en.wikipedia.org/wiki/Incidence_algebra#M%C3%B6bius_function)
+    /// `μ`, where `μ[a][b]` is defined for `a ≤ b` by `μ(a, a) = 1` and
+    /// `μ(a, b) = -Σ μ(a, c)` over every `c` with `a ≤ c < b`. It's 0 when
+    /// `a ≰ b`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if two distinct elements are equal, as the Möbius function is
+    /// only defined for partial orders.
+    ///
+    /// ```
+    /// use orders::partial_order::PartialOrder;
+    ///
+    /// // 0 < 1 < 2
+    /// let mut po = PartialOrder::new_empty(3);
+    /// po.set(0, 1);
+    /// po.set(1, 2);
+    /// po.set(0, 2);
+    /// assert_eq!(po.mobius(), [[1, -1, 0], [0, 1, -1], [0, 0, 1]]);
+    /// ```
+    pub fn mobius(&self) -> Vec<Vec<i64>> {
+        assert!(self.pairs().all(|(a, b)| !self.eq(a, b)), "order has equal elements");
+        let n = self.elements();
+        // If `c < b`, then `c` has fewer elements below it than `b`, so it's
+        // visited first.
+        let mut order: Vec<usize> = (0..n).collect();
+        order.sort_by_cached_key(|&b| self.downset(b).len());
+        let mut mu = vec![vec![0; n]; n];
+        for (a, row) in mu.iter_mut().enumerate() {
+            for &b in &order {
+                if a == b {
+                    row[b] = 1;
+                } else if self.le(a, b) {
+                    row[b] = -self
+                        .interval(a, b)
+                        .iter()
+                        .filter(|&&c| c != b)
+                        .map(|&c| row[c])
+                        .sum::<i64>();
+                }
+            }
+        }
+        mu
+    }
+
+    /// Rename the elements, where element `e` becomes element `perm[e]`, so
+    /// `perm[a] ≤ perm[b]` in the new order if `a ≤ b` in `self`.
+    ///
+    /// Returns `Err` if `perm` is not a permutation of `0..self.elements()`.
+    ///
+    /// ```
+    /// use orders::partial_order::PartialOrder;
+    ///
+    /// let mut po = PartialOrder::new_empty(3);
+    /// po.set(0, 1);
+    /// let relabeled = po.relabel(&[2, 0, 1]).unwrap();
+    /// assert!(relabeled.le(2, 0));
+    /// assert!(!relabeled.le(0, 1));
+    /// ```
+    pub fn relabel(&self, perm: &[usize]) -> Result<PartialOrder, &'static str> {
+        let n = self.elements();
+        if perm.len() != n || !unique_and_bounded(n, perm) {
+            return Err("Not a permutation of the elements");
+        }
+        let mut matrix = MatrixBool::new(n);
+        for a in 0..n {
+            for b in 0..n {
+                matrix[(perm[a], perm[b])] = self.le(a, b);
+            }
+        }
+        Ok(PartialOrder { matrix })
+    }
+
+    /// Returns `true` if there is a bijection between the elements of `self`
+    /// and `other` which preserves the order.
+    ///
+    /// Uses a backtracking search which only maps elements to elements with
+    /// the same number of elements above and below them. This is fast for
+    /// most orders, but takes exponential time in the worst case.
+    ///
+    /// ```
+    /// use orders::partial_order::PartialOrder;
+    ///
+    /// let mut a = PartialOrder::new_empty(3);
+    /// a.set(0, 1);
+    /// let mut b = PartialOrder::new_empty(3);
+    /// b.set(2, 0);
+    /// assert!(a.is_isomorphic(&b));
+    /// b.set(1, 0);
+    /// assert!(!a.is_isomorphic(&b));
+    /// ```
+    pub fn is_isomorphic(&self, other: &PartialOrder) -> bool {
+        let n = self.elements();
+        if n != other.elements() {
+            return false;
+        }
+        let signature = |po: &PartialOrder, e: usize| (po.downset(e).len(), po.upset(e).len());
+        let ours: Vec<(usize, usize)> = (0..n).map(|e| signature(self, e)).collect();
+        let theirs: Vec<(usize, usize)> = (0..n).map(|e| signature(other, e)).collect();
+        let (mut a, mut b) = (ours.clone(), theirs.clone());
+        a.sort_unstable();
+        b.sort_unstable();
+        if a != b {
+            return false;
+        }
+        // Map the elements with the rarest signatures first, to prune early.
+        let mut order: Vec<usize> = (0..n).collect();
+        order.sort_by_cached_key(|&e| (ours.iter().filter(|&&s| s == ours[e]).count(), e));
+        let mut search = IsomorphismSearch {
+            from: self,
+            to: other,
+            order,
+            mapping: vec![0; n],
+            used: vec![false; n],
+            ours,
+            theirs,
+        };
+        search.extend(0)
+    }
+
+    /// Returns `true` if every pair of elements is comparable.
+    pub fn is_total(&self) -> bool {
+        self.incomparable_pairs().next().is_none()
+    }
+
+    /// Returns `true` if no pair of distinct elements is comparable.
+    pub fn is_antichain(&self) -> bool {
+        self.comparable_pairs().next().is_none()
+    }
+
+    /// Returns `true` if incomparability is transitive, i.e. if `a` is
+    /// incomparable to `b` and `b` is incomparable to `c`, then `a` is
+    /// incomparable to `c` (or `a` is `c`). Equal elements count as
+    /// incomparable here, as they end up tied in
+    /// [`PartialOrder::try_to_tied`].
+    ///
+    /// ```
+    /// use orders::partial_order::PartialOrder;
+    ///
+    /// let mut po = PartialOrder::new_empty(3);
+    /// assert!(po.is_weak_order() && po.is_antichain());
+    /// po.set(0, 1);
+    /// assert!(!po.is_weak_order());
+    /// po.set(1, 2);
+    /// assert!(po.is_weak_order() && po.is_total());
+    /// ```
+    pub fn is_weak_order(&self) -> bool {
+        let n = self.elements();
+        let tied = |a, b| self.ord(a, b).is_none_or(|o| o == Ordering::Equal);
+        self.pairs()
+            .filter(|&(a, b)| tied(a, b))
+            .all(|(a, b)| (0..n).all(|c| c == a || c == b || tied(b, c) == tied(a, c)))
+    }
+
+    /// Collapse elements into groups, where `groups[e]` is the group of element
+    /// `e`. In the returned order of `max(groups) + 1` elements, group `g` is
+    /// less than group `h` if some element of `g` is less than or equal to
+    /// some element of `h`.
+    ///
+    /// Returns `None` if this would make two groups less than each other.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `groups.len()` is not equal to the number of elements.
+    ///
+    /// ```
+    /// use orders::partial_order::PartialOrder;
+    ///
+    /// let mut po = PartialOrder::new_empty(4);
+    /// po.set(0, 2);
+    /// po.set(1, 3);
+    /// let q = po.quotient(&[0, 0, 1, 1]).unwrap();
+    /// assert!(q.le(0, 1) && !q.le(1, 0));
+    /// assert!(po.quotient(&[0, 1, 1, 0]).is_none());
+    /// ```
+    #[must_use]
+    pub fn quotient(&self, groups: &[usize]) -> Option<PartialOrder> {
+        assert!(groups.len() == self.elements());
+        let k = groups.iter().max().map_or(0, |m| m + 1);
+        let mut less = vec![false; k * k];
+        for a in 0..self.elements() {
+            for b in 0..self.elements() {
+                if groups[a] != groups[b] && self.le(a, b) {
+                    less[groups[a] * k + groups[b]] = true;
+                }
+            }
+        }
+        PartialOrder::from_matrix(k, &less).ok()
+    }
+
+    /// Returns the order induced on the elements in `keep`, where element `i`
+    /// of the returned order is element `keep[i]` of `self`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `keep` contains duplicates or elements out of range.
+    ///
+    /// ```
+    /// use orders::partial_order::PartialOrder;
+    ///
+    /// let mut po = PartialOrder::new_empty(4);
+    /// po.set(3, 1);
+    /// let r = po.restrict(&[1, 3]);
+    /// assert!(r.le(1, 0) && !r.le(0, 1));
+    /// ```
+    #[must_use]
+    pub fn restrict(&self, keep: &[usize]) -> PartialOrder {
+        assert!(unique_and_bounded(self.elements(), keep));
+        let mut matrix = MatrixBool::new(keep.len());
+        for (i, &a) in keep.iter().enumerate() {
+            for (j, &b) in keep.iter().enumerate() {
+                matrix[(i, j)] = self.matrix[(a, b)];
+            }
+        }
+        PartialOrder { matrix }
+    }
+
+    /// For every element `a`, returns the elements `b` with `a < b` but not
+    /// `b ≤ a`.
+    fn strictly_above(&self) -> Vec<Vec<usize>> {
+        let n = self.elements();
+        (0..n).map(|a| (0..n).filter(|&b| self.le(a, b) && !self.le(b, a)).collect()).collect()
+    }
+
+    /// Convert to a [`Tied`] order, if every element is comparable to every
+    /// element outside its group of mutually incomparable (or equal) elements.
+    /// This is the inverse of [`Tied::to_partial`](Order::to_partial), where
+    /// tied elements are incomparable. Elements in the same group are listed
+    /// in increasing order.
+    ///
+    /// Returns `None` if the order can't be represented as a `Tied`.
+    ///
+    /// ```
+    /// use orders::{Order, tied::Tied};
+    ///
+    /// let tied = Tied::new(vec![2, 0, 1], vec![false, true]);
+    /// let po = tied.clone().to_partial();
+    /// assert_eq!(po.try_to_tied(), Some(Tied::new(vec![2, 0, 1], vec![false, true])));
+    /// ```
+    pub fn try_to_tied(&self) -> Option<Tied> {
+        let n = self.elements();
+        let above: Vec<usize> = self.strictly_above().iter().map(Vec::len).collect();
+        for a in 0..n {
+            for b in 0..n {
+                let strict = self.le(a, b) && !self.le(b, a);
+                if strict != (above[a] > above[b]) {
+                    return None;
+                }
+            }
+        }
+        let mut order: Vec<usize> = (0..n).collect();
+        order.sort_by_key(|&a| above[a]);
+        let tied = order.windows(2).map(|w| above[w[0]] == above[w[1]]).collect();
+        Some(Tied::new(order, tied))
+    }
+
+    /// Returns a minimum set of chains containing every element exactly once,
+    /// each listed from its highest element to its lowest. By [Dilworth's
+    /// theorem](https://en.wikipedia.org/wiki/Dilworth%27s_theorem), the
+    /// number of chains is the [width](PartialOrder::width) of the order.
+    /// Equal elements are comparable, so they may share a chain.
+    ///
+    /// The chains are found using a maximum bipartite matching, taking
+    /// `O(n³)` time for `n` elements.
+    ///
+    /// ```
+    /// use orders::{OrderOwned, partial_order::PartialOrder};
+    ///
+    /// // 0 < 1 < 2 and 3 < 2
+    /// let mut po = PartialOrder::new_empty(4);
+    /// po.set(0, 1);
+    /// po.set(1, 2);
+    /// po.set(0, 2);
+    /// po.set(3, 2);
+    /// let cover = po.chain_cover();
+    /// assert_eq!(cover.len(), 2);
+    /// assert_eq!(cover[0].as_ref().order(), [2, 1, 0]);
+    /// assert_eq!(cover[1].as_ref().order(), [3]);
+    /// ```
+    pub fn chain_cover(&self) -> Vec<ChainI> {
+        let n = self.elements();
+        // A strict order, where equal elements are ordered by index
+        let below = |a: usize, b: usize| self.le(a, b) && (!self.le(b, a) || a < b);
+
+        fn augment(
+            a: usize,
+            below: &impl Fn(usize, usize) -> bool,
+            visited: &mut [bool],
+            pred: &mut [Option<usize>],
+        ) -> bool {
+            for b in 0..visited.len() {
+                if a != b && below(a, b) && !visited[b] {
+                    visited[b] = true;
+                    if pred[b].is_none_or(|p| augment(p, below, visited, pred)) {
+                        pred[b] = Some(a);
+                        return true;
+                    }
+                }
+            }
+            false
+        }
+
+        // `pred[b]` is the element directly below `b` in its chain
+        let mut pred: Vec<Option<usize>> = vec![None; n];
+        let mut visited = vec![false; n];
+        for a in 0..n {
+            visited.fill(false);
+            augment(a, &below, &mut visited, &mut pred);
+        }
+        let mut succ: Vec<Option<usize>> = vec![None; n];
+        for (b, p) in pred.iter().enumerate() {
+            if let Some(a) = *p {
+                succ[a] = Some(b);
+            }
+        }
+        (0..n)
+            .filter(|&a| pred[a].is_none())
+            .map(|bottom| {
+                let mut order = vec![bottom];
+                while let Some(next) = succ[*order.last().unwrap()] {
+                    order.push(next);
+                }
+                order.reverse();
+                ChainI::new(n, order)
+            })
+            .collect()
+    }
+
+    /// The size of the largest antichain, i.e. the largest set of pairwise
+    /// incomparable elements. This is also the number of chains in
+    /// [`PartialOrder::chain_cover`].
+    pub fn width(&self) -> usize {
+        self.chain_cover().len()
+    }
+
+    /// Group the elements into layers, where layer `i` contains the elements
+    /// whose longest chain of strictly smaller elements below them has `i`
+    /// elements. Every layer is an antichain, apart from equal elements, and
+    /// the number of layers is the [height](PartialOrder::height) of the
+    /// order. Elements within a layer are sorted by index.
+    ///
+    /// ```
+    /// use orders::partial_order::PartialOrder;
+    ///
+    /// // 0 < 1 < 2 and 3 < 2
+    /// let mut po = PartialOrder::new_empty(4);
+    /// po.set(0, 1);
+    /// po.set(1, 2);
+    /// po.set(0, 2);
+    /// po.set(3, 2);
+    /// assert_eq!(po.antichain_layers(), [vec![0, 3], vec![1], vec![2]]);
+    /// ```
+    pub fn antichain_layers(&self) -> Vec<Vec<usize>> {
+        let n = self.elements();
+        let strictly_below = |a: usize, b: usize| self.le(a, b) && !self.le(b, a);
+        // Any element has fewer strictly smaller elements than the elements
+        // above it, so this visits the elements in a topological order.
+        let mut order: Vec<usize> = (0..n).collect();
+        order.sort_by_cached_key(|&b| (0..n).filter(|&a| strictly_below(a, b)).count());
+        let mut layer = vec![0; n];
+        for (i, &b) in order.iter().enumerate() {
+            layer[b] = order[..i]
+                .iter()
+                .filter(|&&a| strictly_below(a, b))
+                .map(|&a| layer[a] + 1)
+                .max()
+                .unwrap_or(0);
+        }
+        let mut layers = vec![Vec::new(); layer.iter().max().map_or(0, |&l| l + 1)];
+        for (a, &l) in layer.iter().enumerate() {
+            layers[l].push(a);
+        }
+        layers
+    }
+
+    /// The number of elements in the longest chain of strictly increasing
+    /// elements. This is also the number of layers in
+    /// [`PartialOrder::antichain_layers`].
+    pub fn height(&self) -> usize {
+        self.antichain_layers().len()
+    }
+
+    /// The size of the largest clique in the comparability graph, i.e. the
+    /// largest set of mutually comparable elements. Such a set is a chain, so
+    /// when no two elements are equal this is equal to
+    /// [`PartialOrder::height`], but it's computed independently, by finding
+    /// the longest path down from every element. Equal elements are
+    /// comparable, so they are all part of the same cliques, which can make
+    /// this larger than the height.
+    ///
+    /// ```
+    /// use orders::partial_order::PartialOrder;
+    ///
+    /// let mut po = PartialOrder::new_empty(2);
+    /// po.set(0, 1);
+    /// assert_eq!(po.max_clique_in_comparability(), 2);
+    /// po.set(1, 0);
+    /// assert_eq!((po.max_clique_in_comparability(), po.height()), (2, 1));
+    /// ```
+    pub fn max_clique_in_comparability(&self) -> usize {
+        let n = self.elements();
+        let equal = |a: usize| (0..n).filter(move |&b| self.eq(a, b));
+        let above = |a: usize| (0..n).filter(move |&b| self.le(a, b) && !self.le(b, a));
+        // Every element strictly above `a` has fewer elements strictly above
+        // it than `a`, so they are visited before `a`.
+        let mut order: Vec<usize> = (0..n).collect();
+        order.sort_by_cached_key(|&a| above(a).count());
+        let mut longest = vec![0; n];
+        for &a in &order {
+            longest[a] = equal(a).count() + above(a).map(|b| longest[b]).max().unwrap_or(0);
+        }
+        longest.into_iter().max().unwrap_or(0)
+    }
+
+    /// Returns the number of linear extensions of the order, i.e. the number of
+    /// total orders consistent with it. Equal elements may be ordered either
+    /// way. An order of zero elements has one linear extension.
+    ///
+    /// This takes `O(2ⁿ n²)` time and `O(2ⁿ)` memory for `n` elements, so it
+    /// returns `None` if there are more than 24 elements, unless the order is a
+    /// chain, which always has one linear extension. The result saturates at
+    /// `usize::MAX`.
+    ///
+    /// ```
+    /// use orders::partial_order::PartialOrder;
+    ///
+    /// let mut po = PartialOrder::new_empty(3);
+    /// assert_eq!(po.count_linear_extensions(), Some(6));
+    /// po.set(0, 1);
+    /// assert_eq!(po.count_linear_extensions(), Some(3));
+    /// assert_eq!(PartialOrder::new_empty(25).count_linear_extensions(), None);
+    /// ```
+    pub fn count_linear_extensions(&self) -> Option<usize> {
+        if self.is_chain() {
+            return Some(1);
+        }
+        Some(self.completions()?[0])
+    }
+
+    /// For every element, returns the fraction of linear extensions where it's
+    /// the highest element. This is the probability of each element winning,
+    /// if the order is completed uniformly at random.
+    ///
+    /// Has the same cost as [`PartialOrder::count_linear_extensions`], and
+    /// returns `None` in the same cases.
+    ///
+    /// ```
+    /// use orders::partial_order::PartialOrder;
+    ///
+    /// let mut po = PartialOrder::new_empty(4);
+    /// po.set(0, 1);
+    /// po.set(2, 3);
+    /// assert_eq!(po.top_probabilities(), Some(vec![0.0, 0.5, 0.0, 0.5]));
+    /// ```
+    pub fn top_probabilities(&self) -> Option<Vec<f64>> {
+        let n = self.elements();
+        let above = self.strictly_above();
+        if self.is_chain() {
+            return Some((0..n).map(|x| if above[x].is_empty() { 1.0 } else { 0.0 }).collect());
+        }
+        let table = self.completions()?;
+        let total = table[0] as f64;
+        Some(
+            (0..n)
+                .map(|x| if above[x].is_empty() { table[1 << x] as f64 / total } else { 0.0 })
+                .collect(),
+        )
+    }
+
+    /// Returns `true` if every pair of distinct elements is strictly
+    /// comparable, so there is exactly one linear extension.
+    fn is_chain(&self) -> bool {
+        self.pairs()
+            .all(|(a, b)| matches!(self.ord(a, b), Some(Ordering::Less | Ordering::Greater)))
+    }
+
+    /// Returns a table where entry `placed` is the number of ways to complete a
+    /// linear extension, when the elements in the bitset `placed` have already
+    /// been placed highest. Returns `None` if there are more than
+    /// [`MAX_COMPLETION_ELEMENTS`] elements.
+    fn completions(&self) -> Option<Vec<usize>> {
+        let n = self.elements();
+        if n > MAX_COMPLETION_ELEMENTS {
+            return None;
+        }
+        let above: Vec<usize> = self
+            .strictly_above()
+            .into_iter()
+            .map(|bs| bs.into_iter().fold(0, |mask, b| mask | (1 << b)))
+            .collect();
+        let full = (1usize << n) - 1;
+        let mut table = vec![0usize; full + 1];
+        table[full] = 1;
+        for placed in (0..full).rev() {
+            let mut sum = 0usize;
+            for (x, &ab) in above.iter().enumerate() {
+                let bit = 1 << x;
+                if placed & bit == 0 && ab & placed == ab {
+                    sum = sum.saturating_add(table[placed | bit]);
+                }
+            }
+            table[placed] = sum;
+        }
+        Some(table)
+    }
+
+    /// Returns every linear extension of the order, i.e. every total order
+    /// consistent with it, or `None` if there are more than `max` of them.
+    /// Equal elements may be ordered either way.
+    ///
+    /// The number of extensions can grow factorially with the number of
+    /// elements, an antichain of `n` elements has `n!` extensions, so use
+    /// `max` to limit the time and memory spent.
+    ///
+    /// An order of zero elements returns an empty collection, even though
+    /// [`count_linear_extensions`](Self::count_linear_extensions) counts one
+    /// extension, as a [`ChainDense`] can't store orders of zero elements.
+    ///
+    /// ```
+    /// use orders::{collections::DenseOrders, partial_order::PartialOrder};
+    ///
+    /// let mut po = PartialOrder::new_empty(3);
+    /// po.set(0, 1);
+    /// let extensions = po.extensions_dense(10).unwrap();
+    /// assert_eq!(extensions.len(), 3);
+    /// assert!(po.extensions_dense(2).is_none());
+    /// ```
+    pub fn extensions_dense(&self, max: usize) -> Option<ChainDense> {
+        fn extend(
+            above: &[Vec<usize>],
+            placed: &mut [bool],
+            current: &mut Vec<usize>,
+            out: &mut Vec<usize>,
+            left: &mut usize,
+        ) -> bool {
+            if current.len() == placed.len() {
+                if *left == 0 {
+                    return false;
+                }
+                *left -= 1;
+                out.extend_from_slice(current);
+                return true;
+            }
+            for x in 0..placed.len() {
+                if !placed[x] && above[x].iter().all(|&y| placed[y]) {
+                    placed[x] = true;
+                    current.push(x);
+                    let ok = extend(above, placed, current, out, left);
+                    current.pop();
+                    placed[x] = false;
+                    if !ok {
+                        return false;
+                    }
+                }
+            }
+            true
+        }
+
+        let n = self.elements();
+        let mut res = ChainDense::new(n);
+        if n == 0 {
+            return Some(res);
+        }
+        let above = self.strictly_above();
+        let mut placed = vec![false; n];
+        let mut current = Vec::with_capacity(n);
+        let mut left = max;
+        if extend(&above, &mut placed, &mut current, &mut res.orders, &mut left) {
+            Some(res)
+        } else {
+            None
+        }
+    }
+
     #[must_use]
     pub fn combine(po1: &Self, po2: &Self) -> Self {
         assert!(po1.elements() == po2.elements());
@@ -145,7 +873,9 @@ impl PartialOrder {
         let category_size = self.elements().div_ceil(x);
 
         let mut objs: Vec<usize> = (0..self.elements()).collect();
-        objs.sort_by(|&a, &b| self.ord(a, b).unwrap_or(Ordering::Equal));
+        // Sort by the number of smaller elements, which is a linear extension of
+        // the order.
+        objs.sort_by_cached_key(|&a| (0..self.elements()).filter(|&b| self.le(b, a)).count());
         let mut switches = Vec::new();
         let mut i = 0;
         for xx in objs.windows(2) {
@@ -229,18 +959,32 @@ impl Order for PartialOrder {
     }
 }
 
-/// Like `PartialOrder` but transitive relations may not be set. Created using
-/// [`PartialOrder::to_manual`].
-pub(crate) struct PartialOrderManual {
+/// Like `PartialOrder` but transitive relations may not be set. Relations are
+/// added one at a time, and [`PartialOrderManual::finish`] then adds every
+/// transitive relation.
+///
+/// ```
+/// use orders::partial_order::PartialOrderManual;
+///
+/// // 3 > 1 > 0 and 2 > 0
+/// let mut manual = PartialOrderManual::new(4);
+/// manual.set_chain(&[3, 1, 0]);
+/// manual.set_all(&[(0, 2)]);
+/// let po = manual.finish().unwrap();
+/// assert!(po.le(0, 3) && po.le(0, 2));
+/// assert!(!po.le(1, 2) && !po.le(2, 1));
+/// ```
+pub struct PartialOrderManual {
     matrix: MatrixBool,
 }
 
 impl PartialOrderManual {
-    pub(crate) fn elements(&self) -> usize {
+    pub fn elements(&self) -> usize {
         self.matrix.dim
     }
 
-    pub(crate) fn new(n: usize) -> Self {
+    /// Create `n` elements without any relations between them.
+    pub fn new(n: usize) -> Self {
         let mut matrix = MatrixBool::new(n);
         for i in 0..n {
             matrix[(i, i)] = true;
@@ -249,11 +993,41 @@ impl PartialOrderManual {
     }
 
     /// Set only `i ≤ j`, without setting transitive relations.
-    pub(crate) fn set(&mut self, i: usize, j: usize) {
+    pub fn set(&mut self, i: usize, j: usize) {
         assert!(i < self.elements() && j < self.elements());
         self.matrix[(i, j)] = true;
     }
 
+    /// Set `i ≤ j` for every pair `(i, j)` in `pairs`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if any element is not less than [`PartialOrderManual::elements`].
+    pub fn set_all(&mut self, pairs: &[(usize, usize)]) {
+        for &(i, j) in pairs {
+            self.set(i, j);
+        }
+    }
+
+    /// Make `order` a chain, where earlier elements are greater than later
+    /// elements. Unlike [`PartialOrderManual::set`], this sets the transitive
+    /// relations between the listed elements as well.
+    ///
+    /// # Panics
+    ///
+    /// Panics if any element is not less than [`PartialOrderManual::elements`].
+    pub fn set_chain(&mut self, order: &[usize]) {
+        for (i, &high) in order.iter().enumerate() {
+            for &low in &order[(i + 1)..] {
+                self.set(low, high);
+            }
+        }
+        // A single element has no pair to check in `set`
+        if let [single] = order {
+            assert!(*single < self.elements());
+        }
+    }
+
     pub fn set_ord(&mut self, i: usize, j: usize, o: Ordering) {
         assert!(i < self.elements() && j < self.elements());
         match o {
@@ -266,7 +1040,14 @@ impl PartialOrderManual {
         }
     }
 
-    pub(crate) fn finish(mut self) -> PartialOrder {
+    /// Convert to `PartialOrder`, adding all transitive relations.
+    ///
+    /// Returns `Err(CycleError)` if the relations contain a cycle of distinct
+    /// elements.
+    pub fn finish(mut self) -> Result<PartialOrder, CycleError> {
+        if let Some((a, b)) = self.find_cycle() {
+            return Err(CycleError { a, b });
+        }
         let mut updated = true;
         while updated {
             updated = false;
@@ -281,7 +1062,65 @@ impl PartialOrderManual {
                 }
             }
         }
-        PartialOrder { matrix: self.matrix }
+        Ok(PartialOrder { matrix: self.matrix })
+    }
+
+    /// Find two distinct elements in the same strongly connected component,
+    /// using Tarjan's algorithm.
+    fn find_cycle(&self) -> Option<(usize, usize)> {
+        let n = self.elements();
+        let mut index = vec![usize::MAX; n];
+        let mut low = vec![0; n];
+        let mut on_stack = vec![false; n];
+        let mut stack = Vec::new();
+        let mut counter = 0;
+        for root in 0..n {
+            if index[root] != usize::MAX {
+                continue;
+            }
+            index[root] = counter;
+            low[root] = counter;
+            counter += 1;
+            stack.push(root);
+            on_stack[root] = true;
+            // Simulated call stack of (element, next neighbor to visit)
+            let mut calls = vec![(root, 0)];
+            while let Some((v, next)) = calls.last_mut() {
+                let v = *v;
+                if *next < n {
+                    let w = *next;
+                    *next += 1;
+                    if w == v || !self.matrix[(v, w)] {
+                        continue;
+                    }
+                    if index[w] == usize::MAX {
+                        index[w] = counter;
+                        low[w] = counter;
+                        counter += 1;
+                        stack.push(w);
+                        on_stack[w] = true;
+                        calls.push((w, 0));
+                    } else if on_stack[w] {
+                        low[v] = low[v].min(index[w]);
+                    }
+                } else {
+                    calls.pop();
+                    if let Some(&(u, _)) = calls.last() {
+                        low[u] = low[u].min(low[v]);
+                    }
+                    if low[v] == index[v] {
+                        // `v` is the root of a component, which contains
+                        // everything above it on the stack.
+                        let w = stack.pop().unwrap();
+                        if w != v {
+                            return Some((v, w));
+                        }
+                        on_stack[w] = false;
+                    }
+                }
+            }
+        }
+        None
     }
 
     /// Convert to `PartialOrder`.
@@ -294,46 +1133,191 @@ impl PartialOrderManual {
     }
 }
 
+/// Generates partial orders of less than `g.size()` elements, where some
+/// elements may be equal. Shrinking removes elements or relations.
+#[cfg(any(test, feature = "quickcheck"))]
+impl quickcheck::Arbitrary for PartialOrder {
+    fn arbitrary(g: &mut quickcheck::Gen) -> Self {
+        let n = usize::arbitrary(g) % g.size().max(1);
+
+        // Only add relations going up a random permutation, so there are no
+        // cycles.
+        let mut perm: Vec<usize> = (0..n).collect();
+        for i in (1..n).rev() {
+            perm.swap(i, usize::arbitrary(g) % (i + 1));
+        }
+        let density = u8::arbitrary(g);
+        let mut po = PartialOrderManual::new(n);
+        for i in 0..n {
+            for j in (i + 1)..n {
+                if u8::arbitrary(g) < density {
+                    po.set(perm[i], perm[j]);
+                }
+            }
+        }
+        let po = po.finish().expect("relations only go up `perm`");
+
+        // Make some elements equal to an earlier element, by giving them the
+        // same relations.
+        let equal_density = u8::arbitrary(g) / 2;
+        let mut same_as: Vec<usize> = (0..n).collect();
+        for i in 1..n {
+            if u8::arbitrary(g) < equal_density {
+                same_as[i] = same_as[usize::arbitrary(g) % i];
+            }
+        }
+        let mut matrix = MatrixBool::new(n);
+        for a in 0..n {
+            for b in 0..n {
+                matrix[(a, b)] = po.le(same_as[a], same_as[b]);
+            }
+        }
+        PartialOrder { matrix }
+    }
+
+    fn shrink(&self) -> Box<dyn Iterator<Item = Self>> {
+        let n = self.elements();
+        let po = self.clone();
+        let without_element = (0..n).map(move |x| {
+            let mut smaller = po.clone();
+            smaller.remove_subset(&[x]);
+            smaller
+        });
+
+        // Removing `a ≤ b` keeps the order transitive, as long as there is no
+        // other element between them.
+        let covers: Vec<(usize, usize)> = (0..n)
+            .flat_map(|a| (0..n).map(move |b| (a, b)))
+            .filter(|&(a, b)| {
+                a != b
+                    && self.le(a, b)
+                    && (0..n).all(|c| c == a || c == b || !(self.le(a, c) && self.le(c, b)))
+            })
+            .collect();
+        let po = self.clone();
+        let without_relation = covers.into_iter().map(move |(a, b)| {
+            let mut smaller = po.clone();
+            smaller.matrix[(a, b)] = false;
+            smaller
+        });
+        Box::new(without_element.chain(without_relation))
+    }
+}
+
+/// State of the backtracking search in [`PartialOrder::is_isomorphic`].
+struct IsomorphismSearch<'a> {
+    from: &'a PartialOrder,
+    to: &'a PartialOrder,
+    // The order in which elements of `from` are mapped
+    order: Vec<usize>,
+    mapping: Vec<usize>,
+    used: Vec<bool>,
+    ours: Vec<(usize, usize)>,
+    theirs: Vec<(usize, usize)>,
+}
+
+impl IsomorphismSearch<'_> {
+    /// Try to map the elements `order[i..]`, given that the elements
+    /// `order[..i]` are already mapped.
+    fn extend(&mut self, i: usize) -> bool {
+        let Some(&x) = self.order.get(i) else {
+            return true;
+        };
+        for y in 0..self.to.elements() {
+            if self.used[y] || self.ours[x] != self.theirs[y] {
+                continue;
+            }
+            let consistent = self.order[..i].iter().all(|&p| {
+                let q = self.mapping[p];
+                self.from.le(x, p) == self.to.le(y, q) && self.from.le(p, x) == self.to.le(q, y)
+            });
+            if consistent {
+                self.mapping[x] = y;
+                self.used[y] = true;
+                if self.extend(i + 1) {
+                    return true;
+                }
+                self.used[y] = false;
+            }
+        }
+        false
+    }
+}
+
 #[cfg(test)]
 pub mod tests {
     use std::cmp::Ordering;
 
     use quickcheck::Arbitrary;
+    use rand::{Rng, SeedableRng, rngs::StdRng};
 
     use super::{PartialOrder, PartialOrderManual};
-    use crate::Order;
+    use crate::{Order, OrderOwned, OrderRef, chain::Chain, collections::DenseOrders, tied::Tied};
 
     pub fn valid(po: &PartialOrder) -> bool {
         po.matrix.is_partial_order()
     }
 
-    impl Arbitrary for PartialOrder {
-        fn arbitrary(g: &mut quickcheck::Gen) -> Self {
-            let mut po = PartialOrderManual::new(g.size());
-            for i in 0..po.elements() {
-                for j in 0..po.elements() {
-                    if Arbitrary::arbitrary(g) {
-                        po.set(i, j);
+    #[quickcheck]
+    fn generate(po: PartialOrder) -> bool {
+        valid(&po)
+    }
+
+    #[quickcheck]
+    fn shrink_valid(po: PartialOrder) -> bool {
+        let relations = |p: &PartialOrder| {
+            let n = p.elements();
+            (0..n).flat_map(|a| (0..n).map(move |b| (a, b))).filter(|&(a, b)| p.le(a, b)).count()
+        };
+        po.shrink().all(|smaller| {
+            let fewer_elements = smaller.elements() + 1 == po.elements();
+            let fewer_relations =
+                smaller.elements() == po.elements() && relations(&smaller) + 1 == relations(&po);
+            valid(&smaller) && (fewer_elements || fewer_relations)
+        })
+    }
+
+    #[quickcheck]
+    fn finish_same_as_unchecked(po: PartialOrder) -> bool {
+        let n = po.elements();
+        let manual = || {
+            let mut manual = PartialOrderManual::new(n);
+            for a in 0..n {
+                for b in 0..n {
+                    if po.le(a, b) {
+                        manual.set(a, b);
                     }
                 }
             }
-            po.finish()
-        }
-
-        fn shrink(&self) -> Box<dyn Iterator<Item = Self>> {
-            let pp = self.clone();
-            Box::new((0..self.elements()).map(move |x| {
-                let mut co = pp.clone();
-                let v: Vec<usize> = (0..=x).collect();
-                co.remove_subset(&v);
-                co
-            }))
+            manual
+        };
+        let unchecked = unsafe { manual().finish_unchecked() };
+        // Equal elements form a cycle, which `finish` rejects
+        match manual().finish() {
+            Ok(finished) => finished == unchecked && (0..n).all(|a| (0..a).all(|b| !po.eq(a, b))),
+            Err(e) => {
+                let (a, b) = e.elements();
+                a != b && po.eq(a, b)
+            }
         }
     }
 
     #[quickcheck]
-    fn generate(po: PartialOrder) -> bool {
-        valid(&po)
+    fn set_chain_same_as_to_partial(order: Chain) -> bool {
+        let mut manual = PartialOrderManual::new(order.elements());
+        manual.set_chain(order.as_ref().order);
+        manual.finish() == Ok(order.to_partial())
+    }
+
+    #[test]
+    fn finish_cycle() {
+        let mut manual = PartialOrderManual::new(4);
+        manual.set(0, 1);
+        manual.set(1, 2);
+        manual.set(2, 0);
+        manual.set(2, 3);
+        let (a, b) = manual.finish().unwrap_err().elements();
+        assert!(a != b && a < 3 && b < 3);
     }
 
     #[test]
@@ -370,13 +1354,496 @@ pub mod tests {
         valid(&po3)
     }
 
+    #[quickcheck]
+    fn from_matrix_chain(order: Chain) -> bool {
+        let n = order.elements();
+        let mut less = vec![false; n * n];
+        for (i, &a) in order.order.iter().enumerate() {
+            for &b in &order.order[(i + 1)..] {
+                less[b * n + a] = true;
+            }
+        }
+        PartialOrder::from_matrix(n, &less) == Ok(order.to_partial())
+    }
+
+    /// Create a partial order without equal elements, using a subset of the
+    /// relations in `order`.
+    fn strict(order: &Chain, edges: Vec<bool>) -> PartialOrder {
+        let n = order.elements();
+        let mut less = vec![false; n * n];
+        let mut edges = edges.into_iter().cycle();
+        for (i, &a) in order.order.iter().enumerate() {
+            for &b in &order.order[(i + 1)..] {
+                less[b * n + a] = edges.next().unwrap_or(false);
+            }
+        }
+        PartialOrder::from_matrix(n, &less).unwrap()
+    }
+
+    #[quickcheck]
+    fn matrix_round_trip(order: Chain, edges: Vec<bool>) -> bool {
+        let n = order.elements();
+        let po = strict(&order, edges);
+        let (elements, matrix) = po.to_matrix();
+        elements == n && PartialOrder::from_matrix(elements, &matrix) == Ok(po)
+    }
+
+    #[quickcheck]
+    fn comparable_pairs(po: PartialOrder) -> bool {
+        let n = po.elements();
+        let comparable: Vec<(usize, usize)> = po.comparable_pairs().collect();
+        let incomparable: Vec<(usize, usize)> = po.incomparable_pairs().collect();
+        comparable.len() + incomparable.len() == n * n.saturating_sub(1) / 2
+            && comparable.iter().chain(&incomparable).all(|(a, b)| a < b)
+            && comparable.iter().all(|p| !incomparable.contains(p))
+    }
+
+    #[quickcheck]
+    fn comparable_pairs_chain(order: Chain) -> bool {
+        let po = order.to_partial();
+        po.incomparable_pairs().next().is_none()
+            && PartialOrder::new_empty(po.elements()).comparable_pairs().next().is_none()
+    }
+
+    #[quickcheck]
+    fn quotient_identity(order: Chain, edges: Vec<bool>) -> bool {
+        let po = strict(&order, edges);
+        let groups: Vec<usize> = (0..po.elements()).collect();
+        po.quotient(&groups) == Some(po)
+    }
+
+    #[quickcheck]
+    fn quotient_single(po: PartialOrder) -> bool {
+        let q = po.quotient(&vec![0; po.elements()]).unwrap();
+        q.elements() == usize::from(po.elements() != 0)
+    }
+
+    #[quickcheck]
+    fn restrict_all(po: PartialOrder) -> bool {
+        let all: Vec<usize> = (0..po.elements()).collect();
+        po.restrict(&all) == po
+    }
+
+    #[quickcheck]
+    fn restrict_relations(po: PartialOrder, keep: Vec<usize>) -> bool {
+        let n = po.elements();
+        let mut keep: Vec<usize> = keep.into_iter().filter(|&k| k < n).collect();
+        keep.sort_unstable();
+        keep.dedup();
+        keep.reverse();
+        let r = po.restrict(&keep);
+        valid(&r)
+            && r.elements() == keep.len()
+            && r.pairs().all(|(i, j)| r.ord(i, j) == po.ord(keep[i], keep[j]))
+    }
+
+    #[test]
+    fn extensions_count() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let empty = PartialOrder::new_empty(0);
+        assert!(empty.extensions_dense(usize::MAX).unwrap().is_empty());
+        assert_eq!(empty.count_linear_extensions(), Some(1));
+        for n in 1..7 {
+            for _ in 0..10 {
+                let order = Chain::random(&mut rng, n);
+                let edges = (0..(n * n)).map(|_| rng.random()).collect();
+                let po = strict(&order, edges);
+                let extensions = po.extensions_dense(usize::MAX).unwrap();
+                let count = po.count_linear_extensions().unwrap();
+                assert_eq!(extensions.len(), count);
+                for ext in extensions.iter() {
+                    let ext = ext.to_owned().to_partial();
+                    assert!(
+                        po.pairs()
+                            .all(|(a, b)| po.ord(a, b).is_none() || po.ord(a, b) == ext.ord(a, b))
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn top_probabilities() {
+        let mut rng = StdRng::seed_from_u64(0);
+        for n in 1..7 {
+            let order = Chain::random(&mut rng, n);
+            let p = order.clone().to_partial().top_probabilities().unwrap();
+            assert_eq!(p[order.order[0]], 1.0);
+            let p = PartialOrder::new_empty(n).top_probabilities().unwrap();
+            assert!(p.iter().all(|&x| (x - 1.0 / n as f64).abs() < 1e-9));
+
+            let edges = (0..(n * n)).map(|_| rng.random()).collect();
+            let p = strict(&order, edges).top_probabilities().unwrap();
+            assert!((p.iter().sum::<f64>() - 1.0).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn completions_limit() {
+        let chain = Chain::new((0..100).collect()).to_partial();
+        assert_eq!(chain.count_linear_extensions(), Some(1));
+        let p = chain.top_probabilities().unwrap();
+        assert_eq!(p[0], 1.0);
+        assert!(p[1..].iter().all(|&x| x == 0.0));
+
+        // Two equal elements can be placed in either order
+        let mut po = PartialOrder::new_empty(2);
+        po.set(0, 1);
+        po.set(1, 0);
+        assert_eq!(po.count_linear_extensions(), Some(2));
+
+        let mut po = PartialOrder::new_empty(40);
+        assert_eq!(po.count_linear_extensions(), None);
+        assert_eq!(po.top_probabilities(), None);
+        for a in 1..40 {
+            po.set(0, a);
+        }
+        assert_eq!(po.count_linear_extensions(), None);
+    }
+
+    #[quickcheck]
+    fn chain_cover_partition(po: PartialOrder) -> bool {
+        let n = po.elements();
+        let cover = po.chain_cover();
+        let mut all: Vec<usize> = cover.iter().flat_map(|c| c.order.clone()).collect();
+        all.sort_unstable();
+        let chains = cover.iter().all(|c| c.order.windows(2).all(|w| po.le(w[1], w[0])));
+        // Brute force the largest antichain for small orders
+        let width = if n > 12 {
+            cover.len()
+        } else {
+            (0..(1usize << n))
+                .filter(|set| {
+                    (0..n).all(|a| {
+                        (0..n).all(|b| {
+                            a == b
+                                || set & (1 << a) == 0
+                                || set & (1 << b) == 0
+                                || po.ord(a, b).is_none()
+                        })
+                    })
+                })
+                .map(|set| set.count_ones() as usize)
+                .max()
+                .unwrap()
+        };
+        all == (0..n).collect::<Vec<usize>>() && chains && po.width() == width
+    }
+
+    #[test]
+    fn chain_cover_extremes() {
+        let chain = Chain::new(vec![2, 0, 3, 1]);
+        assert_eq!(chain.clone().to_partial().chain_cover(), [chain.to_incomplete()]);
+        assert_eq!(PartialOrder::new_empty(5).width(), 5);
+    }
+
+    #[quickcheck]
+    fn antichain_layers(po: PartialOrder) -> bool {
+        let layers = po.antichain_layers();
+        let mut layer = vec![usize::MAX; po.elements()];
+        for (i, l) in layers.iter().enumerate() {
+            for &a in l {
+                layer[a] = i;
+            }
+        }
+        // Relations only go up between layers, except between equal elements
+        // which share a layer, and every element above the bottom layer is
+        // above some element in the layer below it.
+        let up = po.comparable_pairs().all(|(a, b)| po.eq(a, b) == (layer[a] == layer[b]));
+        let supported = (0..po.elements()).all(|b| {
+            layer[b] == 0 || (0..po.elements()).any(|a| po.le(a, b) && layer[a] + 1 == layer[b])
+        });
+        !layer.contains(&usize::MAX) && up && supported && po.height() == layers.len()
+    }
+
+    #[test]
+    fn antichain_layers_equal() {
+        // 0 = 1 < 2
+        let mut po = PartialOrder::new_empty(3);
+        po.set(0, 1);
+        po.set(1, 0);
+        po.set(0, 2);
+        po.set(1, 2);
+        assert_eq!(po.antichain_layers(), [vec![0, 1], vec![2]]);
+        assert_eq!(po.height(), 2);
+    }
+
+    #[test]
+    fn height_extremes() {
+        assert_eq!(Chain::new(vec![2, 0, 3, 1]).to_partial().height(), 4);
+        assert_eq!(PartialOrder::new_empty(5).height(), 1);
+        assert_eq!(PartialOrder::new_empty(0).height(), 0);
+    }
+
+    #[quickcheck]
+    fn max_clique_is_height(po: PartialOrder) -> bool {
+        let n = po.elements();
+        let antisymmetric = (0..n).all(|a| (0..a).all(|b| !po.eq(a, b)));
+        let clique = po.max_clique_in_comparability();
+        if antisymmetric { clique == po.height() } else { clique >= po.height() }
+    }
+
+    #[test]
+    fn max_clique_equal() {
+        // 0 = 1 < 2 = 3 = 4, and 5 < 4
+        let mut po = PartialOrder::new_empty(6);
+        for (a, b) in [(0, 1), (2, 3), (3, 4)] {
+            po.set(a, b);
+            po.set(b, a);
+        }
+        po.set(2, 4);
+        po.set(4, 2);
+        for a in [0, 1, 5] {
+            for b in [2, 3, 4] {
+                po.set(a, b);
+            }
+        }
+        assert_eq!(po.height(), 2);
+        assert_eq!(po.max_clique_in_comparability(), 5);
+    }
+
+    #[quickcheck]
+    fn downset_upset(po: PartialOrder) -> bool {
+        (0..po.elements()).all(|e| {
+            let down = po.downset(e);
+            let up = po.upset(e);
+            down.contains(&e)
+                && up.contains(&e)
+                && (0..po.elements()).all(|x| down.contains(&x) == po.le(x, e))
+                && (0..po.elements()).all(|x| up.contains(&x) == po.le(e, x))
+        })
+    }
+
+    #[quickcheck]
+    fn interval_in_upset_and_downset(po: PartialOrder, a: usize, b: usize) -> bool {
+        if po.elements() == 0 {
+            return true;
+        }
+        let (a, b) = (a % po.elements(), b % po.elements());
+        let interval = po.interval(a, b);
+        let (up, down) = (po.upset(a), po.downset(b));
+        let both: Vec<usize> = up.into_iter().filter(|x| down.contains(x)).collect();
+        interval == both
+            && interval.is_empty() != po.le(a, b)
+            && (a != b || interval.iter().all(|&x| po.eq(x, a)))
+    }
+
+    #[test]
+    fn interval_equal() {
+        // 0 = 1 < 2
+        let mut po = PartialOrder::new_empty(3);
+        po.set(0, 1);
+        po.set(1, 0);
+        po.set(0, 2);
+        po.set(1, 2);
+        assert_eq!(po.interval(0, 0), [0, 1]);
+        assert_eq!(po.interval(1, 2), [0, 1, 2]);
+        assert_eq!(po.interval(2, 2), [2]);
+    }
+
+    #[test]
+    fn mobius_boolean_lattice() {
+        // Subsets of {0, 1, 2}, ordered by inclusion
+        let mut manual = PartialOrderManual::new(8);
+        for a in 0..8usize {
+            for b in 0..8usize {
+                if a != b && a & b == a {
+                    manual.set(a, b);
+                }
+            }
+        }
+        let mu = manual.finish().unwrap().mobius();
+        for (a, row) in mu.iter().enumerate() {
+            for (b, &m) in row.iter().enumerate() {
+                let expected = if a & b != a {
+                    0
+                } else if (b & !a).count_ones() % 2 == 0 {
+                    1
+                } else {
+                    -1
+                };
+                assert_eq!(m, expected);
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn mobius_equal() {
+        let mut po = PartialOrder::new_empty(2);
+        po.set(0, 1);
+        po.set(1, 0);
+        let _ = po.mobius();
+    }
+
+    #[quickcheck]
+    fn mobius_inverse_of_zeta(po: PartialOrder) -> bool {
+        // Σ μ(a, c) over a ≤ c ≤ b is 1 if a = b and 0 otherwise
+        let n = po.elements();
+        if (0..n).any(|a| (0..a).any(|b| po.eq(a, b))) {
+            return true;
+        }
+        let mu = po.mobius();
+        (0..n).all(|a| {
+            (0..n).all(|b| {
+                let sum: i64 =
+                    (0..n).filter(|&c| po.le(a, c) && po.le(c, b)).map(|c| mu[a][c]).sum();
+                sum == i64::from(a == b)
+            })
+        })
+    }
+
+    #[quickcheck]
+    fn relabel_round_trip(po: PartialOrder, seed: u64) -> bool {
+        use rand::seq::SliceRandom;
+
+        let mut perm: Vec<usize> = (0..po.elements()).collect();
+        let identity = po.relabel(&perm).unwrap();
+        perm.shuffle(&mut StdRng::seed_from_u64(seed));
+        let mut inverse = vec![0; perm.len()];
+        for (e, &p) in perm.iter().enumerate() {
+            inverse[p] = e;
+        }
+        let relabeled = po.relabel(&perm).unwrap();
+        let back = relabeled.relabel(&inverse).unwrap();
+        let same = |other: &PartialOrder| {
+            (0..po.elements()).all(|a| (0..po.elements()).all(|b| po.le(a, b) == other.le(a, b)))
+        };
+        valid(&relabeled) && same(&identity) && same(&back)
+    }
+
+    #[test]
+    fn relabel_invalid() {
+        let po = PartialOrder::new_empty(3);
+        assert!(po.relabel(&[0, 1]).is_err());
+        assert!(po.relabel(&[0, 1, 1]).is_err());
+        assert!(po.relabel(&[0, 1, 3]).is_err());
+    }
+
+    #[quickcheck]
+    fn isomorphic_relabeled(po: PartialOrder, seed: u64) -> bool {
+        use rand::seq::SliceRandom;
+
+        let mut perm: Vec<usize> = (0..po.elements()).collect();
+        perm.shuffle(&mut StdRng::seed_from_u64(seed));
+        let other = po.relabel(&perm).unwrap();
+        po.is_isomorphic(&po) && po.is_isomorphic(&other) && other.is_isomorphic(&po)
+    }
+
+    #[quickcheck]
+    fn not_isomorphic_different(po: PartialOrder) -> bool {
+        // Adding a relation between incomparable elements changes the
+        // number of comparable pairs.
+        let Some((a, b)) = po.incomparable_pairs().next() else {
+            return true;
+        };
+        let mut manual = PartialOrderManual::new(po.elements());
+        for (p, q) in po.comparable_pairs() {
+            match po.ord(p, q) {
+                Some(Ordering::Less) => manual.set(p, q),
+                _ => manual.set(q, p),
+            }
+        }
+        manual.set(a, b);
+        let Ok(other) = manual.finish() else {
+            return true;
+        };
+        !po.is_isomorphic(&other) && !other.is_isomorphic(&po)
+    }
+
+    #[quickcheck]
+    fn tied_round_trip(tied: Tied) -> bool {
+        // Sort each group, as the order within groups is lost
+        let mut expected = tied.order().to_vec();
+        let mut start = 0;
+        for group in tied.as_ref().iter_groups() {
+            expected[start..(start + group.len())].sort_unstable();
+            start += group.len();
+        }
+        let po = tied.clone().to_partial();
+        po.try_to_tied() == Some(Tied::new(expected, tied.tied().to_vec()))
+    }
+
+    #[test]
+    fn order_kinds() {
+        let chain = Chain::new(vec![3, 1, 0, 2]).to_partial();
+        assert!(chain.is_total() && chain.is_weak_order() && !chain.is_antichain());
+
+        let antichain = PartialOrder::new_empty(4);
+        assert!(!antichain.is_total() && antichain.is_weak_order() && antichain.is_antichain());
+
+        let tied = Tied::new(vec![3, 1, 0, 2], vec![true, false, true]).to_partial();
+        assert!(!tied.is_total() && tied.is_weak_order() && !tied.is_antichain());
+
+        // 0 < 1 < 2 and 3 < 2, where 3 is incomparable to 0 and 1
+        let mut po = PartialOrder::new_empty(4);
+        po.set(0, 1);
+        po.set(1, 2);
+        po.set(3, 2);
+        assert!(!po.is_total() && !po.is_weak_order() && !po.is_antichain());
+
+        let empty = PartialOrder::new_empty(0);
+        assert!(empty.is_total() && empty.is_weak_order() && empty.is_antichain());
+    }
+
+    #[quickcheck]
+    fn weak_order_tied(po: PartialOrder) -> bool {
+        po.is_weak_order() == po.try_to_tied().is_some()
+    }
+
+    #[test]
+    fn weak_order_equal() {
+        // 0 = 1, and 2 is incomparable to both
+        let mut po = PartialOrder::new_empty(3);
+        po.set(0, 1);
+        po.set(1, 0);
+        assert!(po.is_weak_order());
+        assert_eq!(po.try_to_tied(), Some(Tied::new(vec![0, 1, 2], vec![true, true])));
+        // 0 = 1 < 2
+        po.set(0, 2);
+        po.set(1, 2);
+        assert!(po.is_weak_order());
+        // 0 = 1 < 2, and 3 is only below 2
+        let mut po = PartialOrder::new_empty(4);
+        po.set(0, 1);
+        po.set(1, 0);
+        po.set(3, 2);
+        assert!(!po.is_weak_order() && po.try_to_tied().is_none());
+    }
+
+    #[test]
+    fn try_to_tied_none() {
+        let mut po = PartialOrder::new_empty(3);
+        po.set(0, 1);
+        assert!(po.try_to_tied().is_none());
+        po.set(1, 2);
+        assert!(po.try_to_tied().is_some());
+    }
+
     #[quickcheck]
     fn po_categorize(po: PartialOrder, x: usize) -> bool {
+        if po.elements() == 0 {
+            return true;
+        }
         let cats = x % po.elements();
         let vv = po.categorize(cats);
         vv.len() <= cats
     }
 
+    // Sorting with `ord` is not a total order when there are incomparable
+    // elements, which made sorting panic here.
+    #[test]
+    fn po_categorize_sparse() {
+        let mut po = PartialOrderManual::new(21);
+        po.set(18, 8);
+        po.set(10, 9);
+        po.set(20, 9);
+        let po = po.finish().unwrap();
+        let vv = po.categorize(3);
+        assert!(vv.len() <= 3);
+        assert_eq!(vv.iter().map(Vec::len).sum::<usize>(), 21);
+    }
+
     #[quickcheck]
     fn add_remove(po: PartialOrder, x: usize) -> bool {
         if po.elements() == 0 {
@@ -0,0 +1,69 @@
+//! Support for writing property tests with
+//! [`quickcheck`](https://docs.rs/quickcheck) against the types in this crate.
+//! Only available with the `quickcheck` feature.
+//!
+//! ```
+//! use orders::testing::{BoundedArbitrary, std_rng};
+//! use quickcheck::Gen;
+//! use rand::Rng;
+//!
+//! let mut g = Gen::new(10);
+//! let (orders, elements): (usize, usize) = BoundedArbitrary::arbitrary(&mut g);
+//! assert!(orders < 10 && elements < 10);
+//! let _: u64 = std_rng(&mut g).random();
+//! ```
+
+use quickcheck::{Arbitrary, Gen};
+use rand::{SeedableRng, rngs::StdRng};
+
+/// `Gen` contains a rng, but it's a private member so this method is used to
+/// get a standard rng generated from `Gen`.
+pub fn std_rng(g: &mut Gen) -> StdRng {
+    let mut seed = [0u8; 32];
+    for s in &mut seed {
+        *s = Arbitrary::arbitrary(g);
+    }
+    StdRng::from_seed(seed)
+}
+
+/// Like [`Arbitrary`], but generates numerical values less than `g.size()`.
+/// Useful for sizes, such as the number of elements or orders, to avoid
+/// allocating huge collections.
+pub trait BoundedArbitrary: Clone + 'static {
+    fn arbitrary(g: &mut Gen) -> Self;
+}
+
+impl BoundedArbitrary for usize {
+    fn arbitrary(g: &mut Gen) -> Self {
+        let n: Self = Arbitrary::arbitrary(g);
+        n % g.size()
+    }
+}
+
+// Generate elementwise for tuples
+impl<T: BoundedArbitrary> BoundedArbitrary for (T, T) {
+    fn arbitrary(g: &mut Gen) -> Self {
+        (BoundedArbitrary::arbitrary(g), BoundedArbitrary::arbitrary(g))
+    }
+}
+
+impl<T: BoundedArbitrary> BoundedArbitrary for (T, T, T) {
+    fn arbitrary(g: &mut Gen) -> Self {
+        (
+            BoundedArbitrary::arbitrary(g),
+            BoundedArbitrary::arbitrary(g),
+            BoundedArbitrary::arbitrary(g),
+        )
+    }
+}
+
+impl<T: BoundedArbitrary> BoundedArbitrary for (T, T, T, T) {
+    fn arbitrary(g: &mut Gen) -> Self {
+        (
+            BoundedArbitrary::arbitrary(g),
+            BoundedArbitrary::arbitrary(g),
+            BoundedArbitrary::arbitrary(g),
+            BoundedArbitrary::arbitrary(g),
+        )
+    }
+}